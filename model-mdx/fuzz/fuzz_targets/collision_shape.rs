@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use model_mdx::types::{collision::CollisionShape, materialize::Materialized};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, value)) = CollisionShape::parse(data) {
+        let mut output = vec![];
+        let _ = value.encode(&mut output);
+    }
+});
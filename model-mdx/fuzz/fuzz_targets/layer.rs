@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use model_mdx::types::{layer::Layer, materialize::Materialized};
+
+fuzz_target!(|data: &[u8]| {
+    // First byte selects version of the format as the layout depends on it
+    if let Some((version, input)) = data.split_first() {
+        let version = match version % 3 {
+            0 => 800,
+            1 => 900,
+            _ => 1000,
+        };
+        if let Ok((_, value)) = Layer::parse_versioned(Some(version), input) {
+            let mut output = vec![];
+            let _ = value.encode(&mut output);
+        }
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use model_mdx::MdxModel;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(model) = MdxModel::from_slice(data) {
+        let _ = model.to_vec();
+    }
+});
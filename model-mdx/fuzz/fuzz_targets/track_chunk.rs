@@ -0,0 +1,22 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use model_mdx::types::{materialize::Materialized, tracks::TrackChunk};
+
+fn parse_encode<T: Clone + Materialized>(input: &[u8]) {
+    if let Ok((_, value)) = TrackChunk::<T>::parse(input) {
+        let mut output = vec![];
+        let _ = value.encode(&mut output);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    // First byte selects type of values in the tracks
+    if let Some((kind, input)) = data.split_first() {
+        match kind % 4 {
+            0 => parse_encode::<f32>(input),
+            1 => parse_encode::<u32>(input),
+            2 => parse_encode::<[f32; 3]>(input),
+            _ => parse_encode::<[f32; 4]>(input),
+        }
+    }
+});
@@ -0,0 +1,153 @@
+use super::error::Error;
+use crate::types::chunk::*;
+use crate::types::geoset::Geoset;
+use crate::types::layer::*;
+use crate::types::material::Material;
+use std::collections::HashMap;
+
+/// Version of models that classic game mode accepts
+pub const SD_VERSION: u32 = 800;
+
+/// Convert Reforged model into model of version 800. Chunks and fields
+/// that classic format lacks are dropped, only geosets of LOD 0 are kept,
+/// skin weights become matrix groups and materials with HD shader keep only
+/// their first (diffuse) layer.
+pub fn downgrade(model: &Mdlx) -> Result<Mdlx, Error> {
+    let mut root = model.clone();
+    root.vers = Some(Vers {
+        version: SD_VERSION,
+    });
+    root.bpos = None;
+    root.fafx = None;
+    root.corn = None;
+    if let Some(ordered) = &mut root.ordered {
+        let removed = [Bpos::tag(), Fafx::tag(), Corn::tag()];
+        ordered.retain(|tag| !removed.contains(tag));
+    }
+    if let Some(mtls) = &mut root.mtls {
+        mtls.materials.iter_mut().for_each(flatten_material);
+    }
+    strip_lods(&mut root);
+    if let Some(geos) = &mut root.geos {
+        for (index, geoset) in geos.geosets.iter_mut().enumerate() {
+            downgrade_geoset(index, geoset)?;
+        }
+    }
+    Ok(root)
+}
+
+/// Leave only the diffuse layer of HD shader and remove HD fields of layers
+fn flatten_material(material: &mut Material) {
+    if material
+        .shader
+        .take()
+        .is_some_and(|s| !s.content.is_empty())
+    {
+        material.layers.truncate(1);
+    }
+    for layer in &mut material.layers {
+        layer.extra = None;
+        layer.kmte = None;
+        layer.kfc3 = None;
+        layer.kfca = None;
+        layer.kftc = None;
+        if let Some(ordered) = &mut layer.ordered {
+            ordered.retain(|tag| *tag == Kmtf::tag() || *tag == Kmta::tag());
+        }
+    }
+}
+
+/// Remove geosets of lower details and fix references to geosets and
+/// their animations.
+fn strip_lods(root: &mut Mdlx) {
+    let Some(geos) = &mut root.geos else {
+        return;
+    };
+    let mut geoset_ids: HashMap<u32, u32> = HashMap::new();
+    let mut kept = 0;
+    for (i, geoset) in geos.geosets.iter().enumerate() {
+        if geoset.lod_extra.as_ref().is_none_or(|l| l.lod == 0) {
+            geoset_ids.insert(i as u32, kept);
+            kept += 1;
+        }
+    }
+    let mut index = 0;
+    geos.geosets.retain(|_| {
+        index += 1;
+        geoset_ids.contains_key(&(index - 1))
+    });
+
+    let mut animation_ids: HashMap<u32, u32> = HashMap::new();
+    if let Some(geoa) = &mut root.geoa {
+        let mut kept = 0;
+        let mut index = 0;
+        geoa.animations.retain_mut(|animation| {
+            index += 1;
+            let Some(id) = geoset_ids.get(&animation.geoset_id) else {
+                return false;
+            };
+            animation.geoset_id = *id;
+            animation_ids.insert(index - 1, kept);
+            kept += 1;
+            true
+        });
+    }
+    if let Some(chunk) = &mut root.bone {
+        for bone in &mut chunk.bones {
+            if bone.geoset_id != u32::MAX {
+                bone.geoset_id = geoset_ids.get(&bone.geoset_id).copied().unwrap_or(u32::MAX);
+            }
+            if bone.geoset_animation_id != u32::MAX {
+                bone.geoset_animation_id = animation_ids
+                    .get(&bone.geoset_animation_id)
+                    .copied()
+                    .unwrap_or(u32::MAX);
+            }
+        }
+    }
+}
+
+/// Remove HD fields of the geoset. Bones of every vertex with non zero
+/// weight form a matrix group, so the weights themselves are lost.
+fn downgrade_geoset(index: usize, geoset: &mut Geoset) -> Result<(), Error> {
+    geoset.lod_extra = None;
+    geoset.tangents = None;
+    if let Some(ordered) = &mut geoset.ordered {
+        ordered.clear();
+    }
+    let Some(skin) = geoset.skin.take() else {
+        return Ok(());
+    };
+    let mut groups: Vec<Vec<u32>> = vec![];
+    let mut lookup: HashMap<Vec<u32>, usize> = HashMap::new();
+    let mut vertex_groups = vec![];
+    for v in 0..geoset.vertex_positions.len() {
+        let (bones, weights) = skin.vertex(v).unwrap_or_default();
+        let mut group: Vec<u32> = bones
+            .iter()
+            .zip(weights)
+            .filter(|(_, w)| *w > 0)
+            .filter_map(|(b, _)| geoset.matrix_indicies.get(usize::from(*b)).copied())
+            .collect();
+        group.sort_unstable();
+        group.dedup();
+        if group.is_empty() {
+            group.extend(geoset.matrix_indicies.first());
+        }
+        let group_index = *lookup.entry(group).or_insert_with_key(|group| {
+            groups.push(group.clone());
+            groups.len() - 1
+        });
+        vertex_groups.push(group_index);
+    }
+    if groups.len() > 256 {
+        return Err(Error::TooManyMatrixGroups {
+            geoset: index,
+            count: groups.len(),
+        });
+    }
+    geoset.vertex_groups = vertex_groups.into_iter().map(|g| g as u8).collect();
+    geoset.matrix_groups = groups.iter().map(|g| g.len() as u32).collect();
+    geoset.matrix_indicies = groups.concat();
+    Ok(())
+}
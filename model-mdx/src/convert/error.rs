@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+/// Errors that occur while converting models between SD and HD versions
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("Geoset {geoset} needs {count} matrix groups, SD geoset can hold at most 256")]
+    TooManyMatrixGroups { geoset: usize, count: usize },
+    #[error("Geoset {geoset} is bound to {count} nodes, HD skin can refer at most 256")]
+    TooManyBones { geoset: usize, count: usize },
+    #[error("Node object id {object_id} is out of range, model has {count} nodes")]
+    ObjectIdOutOfRange { object_id: u32, count: usize },
+}
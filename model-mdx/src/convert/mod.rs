@@ -0,0 +1,9 @@
+//! Conversion between classic SD models of version 800 and Reforged HD
+//! models of versions 900 and 1000.
+mod downgrade;
+pub mod error;
+mod upgrade;
+
+pub use downgrade::{downgrade, SD_VERSION};
+pub use error::Error;
+pub use upgrade::{upgrade, UpgradeOptions, HD_SHADER, HD_VERSION};
@@ -0,0 +1,222 @@
+use super::error::Error;
+use crate::types::chunk::utils::Literal;
+use crate::types::chunk::*;
+use crate::types::geoset::*;
+use crate::types::layer::*;
+use crate::types::material::Material;
+use crate::types::texture::Texture;
+
+/// Version of Reforged models that are produced by [upgrade]
+pub const HD_VERSION: u32 = 1000;
+
+/// Shader of HD materials that are produced by [upgrade]
+pub const HD_SHADER: &str = "Shader_HD_DefaultUnit";
+
+/// Textures of placeholder layers of HD materials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeOptions {
+    pub normal_texture: String,
+    pub orm_texture: String,
+    pub emissive_texture: String,
+    pub reflections_texture: String,
+}
+
+impl Default for UpgradeOptions {
+    fn default() -> Self {
+        UpgradeOptions {
+            normal_texture: "Textures\\normal.blp".to_owned(),
+            orm_texture: "Textures\\orm.blp".to_owned(),
+            emissive_texture: "Textures\\Black32.blp".to_owned(),
+            reflections_texture: "ReplaceableTextures\\EnvironmentMap.blp".to_owned(),
+        }
+    }
+}
+
+/// Convert classic model into Reforged model of version 1000. Materials get
+/// HD shader with the diffuse layer of the classic material and placeholder
+/// layers, geosets get tangents, skin and LOD 0. Parts that are already in
+/// HD form are kept as is.
+pub fn upgrade(model: &Mdlx, options: &UpgradeOptions) -> Result<Mdlx, Error> {
+    let mut root = model.clone();
+    root.vers = Some(Vers {
+        version: HD_VERSION,
+    });
+    if let Some(mut mtls) = root.mtls.take() {
+        for material in &mut mtls.materials {
+            upgrade_material(&mut root, material, options);
+        }
+        root.mtls = Some(mtls);
+    }
+    if let Some(geos) = &mut root.geos {
+        for (index, geoset) in geos.geosets.iter_mut().enumerate() {
+            upgrade_geoset(index, geoset)?;
+        }
+    }
+    if root.bpos.is_none() {
+        add_bind_poses(&mut root)?;
+    }
+    Ok(root)
+}
+
+/// Index of texture with the file name and replaceable id, adds the texture
+/// if the model doesn't have it.
+fn texture(root: &mut Mdlx, file_name: &str, replaceable_id: u32) -> u32 {
+    let textures = &mut root.texs.get_or_insert(Texs { textures: vec![] }).textures;
+    let found = textures
+        .iter()
+        .position(|t| t.replaceable_id == replaceable_id && t.file_name.content == file_name);
+    let index = found.unwrap_or_else(|| {
+        textures.push(Texture {
+            replaceable_id,
+            file_name: Literal {
+                content: file_name.to_owned(),
+            },
+            flags: 0,
+        });
+        textures.len() - 1
+    });
+    index as u32
+}
+
+/// Replace layers of classic material with layers of HD shader: diffuse,
+/// normal, ORM, emissive, team color and reflections.
+fn upgrade_material(root: &mut Mdlx, material: &mut Material, options: &UpgradeOptions) {
+    if material
+        .shader
+        .as_ref()
+        .is_some_and(|s| !s.content.is_empty())
+    {
+        return;
+    }
+    // Team color and glow layers refer replaceable textures
+    let replaceable = |layer: &Layer| {
+        root.texs
+            .as_ref()
+            .and_then(|c| c.textures.get(layer.texture_id as usize))
+            .is_some_and(|t| t.replaceable_id != 0)
+    };
+    let Some(position) = material
+        .layers
+        .iter()
+        .position(|l| !replaceable(l))
+        .or((!material.layers.is_empty()).then_some(0))
+    else {
+        return;
+    };
+    let mut diffuse = material.layers[position].clone();
+    // Layers below were seen through the diffuse texture, HD shader takes
+    // team color from ORM texture instead.
+    if position > 0 {
+        diffuse.filter_mode = FilterMode::None;
+    }
+    diffuse.extra = Some(LayerExt {
+        emissive_gain: 1.0,
+        fresnel_color: [1.0; 3],
+        fresnel_opacity: 0.0,
+        fresnel_team_color: 0.0,
+    });
+    let placeholders = [
+        texture(root, &options.normal_texture, 0),
+        texture(root, &options.orm_texture, 0),
+        texture(root, &options.emissive_texture, 0),
+        texture(root, "", 1),
+        texture(root, &options.reflections_texture, 0),
+    ];
+    let mut layers = vec![diffuse.clone()];
+    for texture_id in placeholders {
+        layers.push(Layer {
+            texture_id,
+            kmtf: None,
+            ..diffuse.clone()
+        });
+    }
+    material.shader = Some(Literal {
+        content: HD_SHADER.to_owned(),
+    });
+    material.layers = layers;
+}
+
+/// Add HD fields to the geoset. Nodes of matrix group share weight of the
+/// vertex equally, groups of more than four nodes keep the first four.
+fn upgrade_geoset(index: usize, geoset: &mut Geoset) -> Result<(), Error> {
+    if geoset.lod_extra.is_none() {
+        geoset.lod_extra = Some(LodExtra {
+            lod: 0,
+            lod_name: Literal {
+                content: String::new(),
+            },
+        });
+    }
+    if geoset.tangents.is_none() {
+        geoset.tangents = Some(Tangents {
+            tangents: geoset.smooth_tangents(),
+        });
+    }
+    if geoset.skin.is_none() {
+        let groups: Vec<Vec<u32>> = geoset.matrices().into_iter().map(|g| g.to_vec()).collect();
+        let mut indices: Vec<u32> = vec![];
+        let mut skin = vec![];
+        let mut vertex_groups = vec![];
+        for group in &geoset.vertex_groups {
+            let group = groups.get(usize::from(*group)).map_or(&[][..], |g| g);
+            let mut bones = [0; 4];
+            let mut weights = [0; 4];
+            let count = group.len().min(4);
+            for (k, id) in group.iter().take(count).enumerate() {
+                let position = indices.iter().position(|i| i == id);
+                bones[k] = position.unwrap_or_else(|| {
+                    indices.push(*id);
+                    indices.len() - 1
+                }) as u8;
+                weights[k] = (255 / count) as u8;
+            }
+            // Weights must sum up to 255, the first node takes the remainder
+            if count > 0 {
+                weights[0] += (255 % count) as u8;
+            }
+            skin.extend(bones);
+            skin.extend(weights);
+            vertex_groups.push(bones[0]);
+        }
+        if indices.len() > 256 {
+            return Err(Error::TooManyBones {
+                geoset: index,
+                count: indices.len(),
+            });
+        }
+        geoset.matrix_groups = vec![1; indices.len()];
+        geoset.matrix_indicies = indices;
+        geoset.vertex_groups = vertex_groups;
+        geoset.skin = Some(Skin { skin });
+    }
+    if let Some(ordered) = &mut geoset.ordered {
+        for tag in [Tangents::tag(), Skin::tag()] {
+            if !ordered.contains(&tag) {
+                ordered.push(tag);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Nodes of classic models have no rotation or scale at rest and are placed
+/// at their pivots, so each bind matrix is a translation by the node pivot.
+fn add_bind_poses(root: &mut Mdlx) -> Result<(), Error> {
+    let count = root.nodes().count();
+    if count == 0 {
+        return Ok(());
+    }
+    let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+    let mut matrices = vec![identity; count];
+    for node in root.nodes() {
+        let object_id = node.object_id;
+        let out_of_range = Error::ObjectIdOutOfRange { object_id, count };
+        let matrix = matrices.get_mut(object_id as usize).ok_or(out_of_range)?;
+        matrix[9..].copy_from_slice(&root.pivot(object_id));
+    }
+    root.bpos = Some(Bpos { matrices });
+    if let Some(ordered) = &mut root.ordered {
+        ordered.push(Bpos::tag());
+    }
+    Ok(())
+}
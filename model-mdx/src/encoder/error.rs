@@ -10,4 +10,6 @@ pub enum Error {
         expected: usize, 
         passed: usize,
     },
+    #[error("Failed to write output: {0}")]
+    Io(#[from] std::io::Error),
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ pub(crate) mod primitives;
 
 use crate::types::{materialize::Materialized, MdxModel};
 use error::Error;
+use std::io::Write;
 
 /// Encode MDX model into bytes
 pub fn encode_mdx(model: &MdxModel) -> Result<Vec<u8>, Error> {
@@ -10,3 +11,14 @@ pub fn encode_mdx(model: &MdxModel) -> Result<Vec<u8>, Error> {
     model.root.encode(&mut output)?;
     Ok(output)
 }
+
+/// Encode MDX model into the writer. Top level chunks are written as soon as
+/// they are encoded, so the whole file is never kept in memory.
+pub fn write_mdx<W: Write>(model: &MdxModel, writer: &mut W) -> Result<(), Error> {
+    let mut buffer = vec![];
+    model.root.encode_chunks(&mut buffer, |buffer| {
+        writer.write_all(buffer)?;
+        buffer.clear();
+        Ok(())
+    })
+}
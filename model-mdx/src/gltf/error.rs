@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// Errors that occur while converting models to and from glTF
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to serialize glTF document: {0}")]
+    Json(#[from] ::gltf::json::Error),
+    #[error("Failed to write binary glTF: {0}")]
+    Glb(#[from] ::gltf::Error),
+    #[error("Failed to read glTF: {0}")]
+    Read(::gltf::Error),
+    #[error("Data of buffer {0} is not available")]
+    MissingBuffer(usize),
+    #[error(
+        "Primitive {primitive} of mesh {mesh} has {count} vertices, geoset can hold at most 65536"
+    )]
+    TooManyVertices {
+        mesh: usize,
+        primitive: usize,
+        count: usize,
+    },
+    #[error("Primitive {primitive} of mesh {mesh} is bound to {count} bone combinations, geoset can hold at most 256")]
+    TooManyBones {
+        mesh: usize,
+        primitive: usize,
+        count: usize,
+    },
+}
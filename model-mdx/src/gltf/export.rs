@@ -0,0 +1,665 @@
+use super::error::Error;
+use super::{FRAMES_PER_SECOND, Z_UP_TO_Y_UP};
+use crate::types::chunk::Mdlx;
+use crate::types::geoset::Geoset;
+use crate::types::node::Node;
+use crate::types::sequence::Sequence;
+use crate::types::tracks::*;
+use crate::types::MdxModel;
+use ::gltf::binary::{Glb, Header};
+use ::gltf::json::accessor::{ComponentType, GenericComponentType, Type};
+use ::gltf::json::animation::{Channel, Interpolation, Property, Sampler, Target};
+use ::gltf::json::buffer::{self, View};
+use ::gltf::json::mesh::{Mode, Primitive, Semantic};
+use ::gltf::json::scene::UnitQuaternion;
+use ::gltf::json::validation::{Checked, USize64};
+use ::gltf::json::{self, Accessor, Index};
+use log::*;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+/// Exported glTF document with its single binary buffer
+#[derive(Debug, Clone)]
+pub struct GltfAsset {
+    /// Document that refers the binary buffer by index 0
+    pub document: json::Root,
+    /// Content of the binary buffer
+    pub buffer: Vec<u8>,
+}
+
+impl GltfAsset {
+    /// JSON of `.gltf` file, the buffer is expected to be saved at `bin_uri`
+    /// relative to the file.
+    pub fn to_gltf(&self, bin_uri: &str) -> Result<String, Error> {
+        let mut document = self.document.clone();
+        if let Some(buffer) = document.buffers.first_mut() {
+            buffer.uri = Some(bin_uri.to_owned());
+        }
+        Ok(document.to_string_pretty()?)
+    }
+
+    /// Document and buffer packed into single `.glb` file
+    pub fn to_glb(&self) -> Result<Vec<u8>, Error> {
+        let glb = Glb {
+            header: Header {
+                magic: *b"glTF",
+                version: 2,
+                // Calculated by the writer
+                length: 0,
+            },
+            json: Cow::Owned(self.document.to_vec()?),
+            bin: (!self.buffer.is_empty()).then_some(Cow::Borrowed(self.buffer.as_slice())),
+        };
+        Ok(glb.to_vec()?)
+    }
+}
+
+/// Convert model into glTF document. Vertices are skinned to bones and
+/// helpers, each sequence gives an animation clip with the keys of
+/// `KGTR`, `KGRT` and `KGSC` tracks that fall into the sequence interval.
+pub fn export_gltf(model: &MdxModel) -> GltfAsset {
+    let mut exporter = Exporter {
+        model: &model.root,
+        document: json::Root::default(),
+        buffer: vec![],
+        joints: vec![],
+        joint_ids: HashMap::new(),
+        rest_translations: vec![],
+    };
+    exporter.export();
+    let Exporter {
+        mut document,
+        mut buffer,
+        ..
+    } = exporter;
+    if !buffer.is_empty() {
+        align(&mut buffer);
+        document.push(json::Buffer {
+            byte_length: USize64::from(buffer.len()),
+            name: None,
+            uri: None,
+            extensions: None,
+            extras: Default::default(),
+        });
+    }
+    GltfAsset { document, buffer }
+}
+
+fn channel(sampler: usize, node: usize, property: Property) -> Channel {
+    Channel {
+        sampler: Index::new(sampler as u32),
+        target: Target {
+            extensions: None,
+            extras: Default::default(),
+            node: Index::new(node as u32),
+            path: Checked::Valid(property),
+        },
+        extensions: None,
+        extras: Default::default(),
+    }
+}
+
+fn align(buffer: &mut Vec<u8>) {
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(0);
+    }
+}
+
+fn vector_type(size: usize) -> Type {
+    match size {
+        1 => Type::Scalar,
+        2 => Type::Vec2,
+        3 => Type::Vec3,
+        4 => Type::Vec4,
+        _ => Type::Mat4,
+    }
+}
+
+struct Exporter<'a> {
+    model: &'a Mdlx,
+    document: json::Root,
+    buffer: Vec<u8>,
+    /// Bones and helpers, index in the vector is index of the joint and of
+    /// the glTF node.
+    joints: Vec<&'a Node>,
+    /// Joint index by object id
+    joint_ids: HashMap<u32, usize>,
+    /// Translation of each joint relative to its parent when the model is
+    /// not animated.
+    rest_translations: Vec<[f32; 3]>,
+}
+
+impl<'a> Exporter<'a> {
+    fn export(&mut self) {
+        self.document.asset.generator = Some("model-mdx".to_owned());
+        let roots = self.skeleton();
+        let mut children = roots;
+        if let Some(mesh) = self.mesh() {
+            let skin = self.skin();
+            children.push(self.document.push(json::Node {
+                mesh: Some(mesh),
+                skin,
+                ..Default::default()
+            }));
+        }
+        let name = self
+            .model
+            .modl
+            .as_ref()
+            .map(|modl| modl.name.text().to_owned());
+        let root = self.document.push(json::Node {
+            name: name.clone(),
+            children: Some(children),
+            rotation: Some(UnitQuaternion(Z_UP_TO_Y_UP)),
+            ..Default::default()
+        });
+        let scene = self.document.push(json::Scene {
+            extensions: None,
+            extras: Default::default(),
+            name,
+            nodes: vec![root],
+        });
+        self.document.scene = Some(scene);
+        self.animations();
+    }
+
+    /// Create joint nodes and return roots of the hierarchy
+    fn skeleton(&mut self) -> Vec<Index<json::Node>> {
+        let model = self.model;
+        let bones = model.bone.iter().flat_map(|c| &c.bones).map(|b| &b.node);
+        let helpers = model.help.iter().flat_map(|c| &c.helpers);
+        self.joints = bones.chain(helpers).collect();
+        self.joint_ids = self
+            .joints
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.object_id, i))
+            .collect();
+        let parents = self.joint_parents();
+        let mut children = vec![vec![]; self.joints.len()];
+        let mut roots = vec![];
+        for (i, joint) in self.joints.clone().into_iter().enumerate() {
+            let pivot = model.pivot(joint.object_id);
+            let origin = parents[i].map_or([0.0; 3], |p| model.pivot(self.joints[p].object_id));
+            let translation = [
+                pivot[0] - origin[0],
+                pivot[1] - origin[1],
+                pivot[2] - origin[2],
+            ];
+            self.rest_translations.push(translation);
+            let index = self.document.push(json::Node {
+                name: Some(joint.name.text().to_owned()),
+                translation: Some(translation),
+                ..Default::default()
+            });
+            match parents[i] {
+                Some(parent) => children[parent].push(index),
+                None => roots.push(index),
+            }
+        }
+        for (node, children) in self.document.nodes.iter_mut().zip(children) {
+            if !children.is_empty() {
+                node.children = Some(children);
+            }
+        }
+        roots
+    }
+
+    /// Find parent joint of each joint. Nodes that are not joints are skipped
+    /// in the chain of parents, cycles are broken.
+    fn joint_parents(&self) -> Vec<Option<usize>> {
+        let nodes: HashMap<u32, &Node> = self.model.nodes().map(|n| (n.object_id, n)).collect();
+        let mut parents: Vec<Option<usize>> = self
+            .joints
+            .iter()
+            .map(|joint| {
+                let mut parent_id = joint.parent_id;
+                for _ in 0..nodes.len() {
+                    if let Some(parent) = self.joint_ids.get(&parent_id) {
+                        return Some(*parent);
+                    }
+                    parent_id = nodes.get(&parent_id)?.parent_id;
+                }
+                None
+            })
+            .collect();
+        for i in 0..parents.len() {
+            let mut current = parents[i];
+            for _ in 0..parents.len() {
+                match current {
+                    Some(p) if p == i => {
+                        warn!("Node {} is a parent of itself", self.joints[i].object_id);
+                        parents[i] = None;
+                        break;
+                    }
+                    Some(p) => current = parents[p],
+                    None => break,
+                }
+            }
+        }
+        parents
+    }
+
+    fn skin(&mut self) -> Option<Index<json::Skin>> {
+        if self.joints.is_empty() {
+            return None;
+        }
+        // Joints are placed at their pivots in the bind pose
+        let matrices: Vec<[f32; 16]> = self
+            .joints
+            .iter()
+            .map(|joint| {
+                let pivot = self.model.pivot(joint.object_id);
+                let mut matrix = [0.0; 16];
+                matrix[0] = 1.0;
+                matrix[5] = 1.0;
+                matrix[10] = 1.0;
+                matrix[15] = 1.0;
+                matrix[12] = -pivot[0];
+                matrix[13] = -pivot[1];
+                matrix[14] = -pivot[2];
+                matrix
+            })
+            .collect();
+        let inverse_bind_matrices = self.floats(&matrices, false, None);
+        let joints = (0..self.joints.len() as u32).map(Index::new).collect();
+        Some(self.document.push(json::Skin {
+            extensions: None,
+            extras: Default::default(),
+            inverse_bind_matrices: Some(inverse_bind_matrices),
+            joints,
+            name: None,
+            skeleton: None,
+        }))
+    }
+
+    fn mesh(&mut self) -> Option<Index<json::Mesh>> {
+        let mut primitives = vec![];
+        for (i, geoset) in self.model.geos.iter().flat_map(|c| &c.geosets).enumerate() {
+            match self.primitive(geoset) {
+                Some(primitive) => primitives.push(primitive),
+                None => warn!("Geoset {} has no triangles, skipping it", i),
+            }
+        }
+        if primitives.is_empty() {
+            return None;
+        }
+        Some(self.document.push(json::Mesh {
+            extensions: None,
+            extras: Default::default(),
+            name: None,
+            primitives,
+            weights: None,
+        }))
+    }
+
+    fn primitive(&mut self, geoset: &Geoset) -> Option<Primitive> {
+        let count = geoset.vertex_positions.len();
+        let indices: Vec<u16> = geoset
+            .triangles()
+            .into_iter()
+            .filter(|t| t.iter().all(|i| (*i as usize) < count))
+            .flatten()
+            .collect();
+        if indices.is_empty() {
+            return None;
+        }
+        let target = Some(buffer::Target::ArrayBuffer);
+        let mut attributes = BTreeMap::new();
+        let positions = self.floats(&geoset.vertex_positions, true, target);
+        attributes.insert(Checked::Valid(Semantic::Positions), positions);
+        if geoset.vertex_normals.len() == count {
+            let normals = self.floats(&geoset.vertex_normals, false, target);
+            attributes.insert(Checked::Valid(Semantic::Normals), normals);
+        }
+        for (i, set) in geoset.texture_coordinate_sets.iter().enumerate() {
+            if set.texture_coordinates.len() == count {
+                let coords = self.floats(&set.texture_coordinates, false, target);
+                attributes.insert(Checked::Valid(Semantic::TexCoords(i as u32)), coords);
+            }
+        }
+        if !self.joints.is_empty() {
+            let (joints, weights) = self.vertex_weights(geoset);
+            let bytes: Vec<u8> = joints
+                .iter()
+                .flatten()
+                .flat_map(|j| j.to_le_bytes())
+                .collect();
+            let joints = self.accessor(&bytes, count, ComponentType::U16, Type::Vec4, None, target);
+            attributes.insert(Checked::Valid(Semantic::Joints(0)), joints);
+            let weights = self.floats(&weights, false, target);
+            attributes.insert(Checked::Valid(Semantic::Weights(0)), weights);
+        }
+        let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let indices = self.accessor(
+            &bytes,
+            indices.len(),
+            ComponentType::U16,
+            Type::Scalar,
+            None,
+            Some(buffer::Target::ElementArrayBuffer),
+        );
+        Some(Primitive {
+            attributes,
+            extensions: None,
+            extras: Default::default(),
+            indices: Some(indices),
+            material: None,
+            mode: Checked::Valid(Mode::Triangles),
+            targets: None,
+        })
+    }
+
+    /// Joints and weights of vertices either from HD skin or from matrix
+    /// groups. Nodes of matrix group have equal weights.
+    fn vertex_weights(&self, geoset: &Geoset) -> (Vec<[u16; 4]>, Vec<[f32; 4]>) {
+        let matrices = geoset.matrices();
+        let mut all_joints = vec![];
+        let mut all_weights = vec![];
+        for v in 0..geoset.vertex_positions.len() {
+            let mut joints = [0; 4];
+            let mut weights = [0.0; 4];
+            if let Some(skin) = &geoset.skin {
+                if let Some((bones, bone_weights)) = skin.vertex(v) {
+                    for k in 0..4 {
+                        let joint = geoset
+                            .matrix_indicies
+                            .get(bones[k] as usize)
+                            .and_then(|id| self.joint_ids.get(id));
+                        if let Some(joint) = joint.filter(|_| bone_weights[k] > 0) {
+                            joints[k] = *joint as u16;
+                            weights[k] = bone_weights[k] as f32 / 255.0;
+                        }
+                    }
+                }
+            } else {
+                let group = geoset
+                    .vertex_groups
+                    .get(v)
+                    .and_then(|g| matrices.get(*g as usize))
+                    .copied()
+                    .unwrap_or_default();
+                let group_joints: Vec<usize> = group
+                    .iter()
+                    .filter_map(|id| self.joint_ids.get(id))
+                    .copied()
+                    .take(4)
+                    .collect();
+                for (k, joint) in group_joints.iter().enumerate() {
+                    joints[k] = *joint as u16;
+                    weights[k] = 1.0 / group_joints.len() as f32;
+                }
+            }
+            // glTF requires weights that sum up to one
+            let sum: f32 = weights.iter().sum();
+            if sum > 0.0 {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            } else {
+                weights[0] = 1.0;
+            }
+            all_joints.push(joints);
+            all_weights.push(weights);
+        }
+        (all_joints, all_weights)
+    }
+
+    fn animations(&mut self) {
+        let sequences = self.model.seqs.iter().flat_map(|c| &c.sequences);
+        for sequence in sequences {
+            let mut channels = vec![];
+            let mut samplers = vec![];
+            for (i, joint) in self.joints.clone().into_iter().enumerate() {
+                let mut add = |property, sampler: Option<Sampler>| {
+                    if let Some(sampler) = sampler {
+                        channels.push(channel(samplers.len(), i, property));
+                        samplers.push(sampler);
+                    }
+                };
+                if let Some(t) = &joint.kgtr {
+                    let offset = self.rest_translations[i];
+                    add(
+                        Property::Translation,
+                        self.sampler(&t.0, sequence, offset, false),
+                    );
+                }
+                if let Some(t) = &joint.kgrt {
+                    // Component-wise splines don't keep quaternions normalized
+                    add(
+                        Property::Rotation,
+                        self.sampler(&t.0, sequence, [0.0; 4], true),
+                    );
+                }
+                if let Some(t) = &joint.kgsc {
+                    add(
+                        Property::Scale,
+                        self.sampler(&t.0, sequence, [0.0; 3], false),
+                    );
+                }
+            }
+            // Static sequences hold the rest pose, so the clip keeps the name
+            // and the length of the sequence
+            if channels.is_empty() {
+                let Some(sampler) = self.rest_sampler(sequence) else {
+                    warn!(
+                        "Sequence {} is skipped, there are no nodes to animate",
+                        sequence.name.text()
+                    );
+                    continue;
+                };
+                channels.push(channel(0, 0, Property::Translation));
+                samplers.push(sampler);
+            }
+            self.document.push(json::Animation {
+                extensions: None,
+                extras: Default::default(),
+                channels,
+                name: Some(sequence.name.text().to_owned()),
+                samplers,
+            });
+        }
+    }
+
+    /// Keys of the track inside the sequence, values are shifted by `offset`.
+    /// Tracks of global sequences are exported entirely.
+    fn sampler<const N: usize>(
+        &mut self,
+        track: &TrackChunk<[f32; N]>,
+        sequence: &Sequence,
+        offset: [f32; N],
+        linear_only: bool,
+    ) -> Option<Sampler> {
+        let [start, end] = sequence.interval.map(i64::from);
+        let global = track.global_sequence_id != u32::MAX;
+        let mut keys: Vec<(f32, &Track<[f32; N]>)> = vec![];
+        for key in &track.tracks {
+            let frame = i64::from(key.frame());
+            let time = if global && frame >= 0 {
+                frame
+            } else if !global && frame >= start && frame <= end {
+                frame - start
+            } else {
+                continue;
+            };
+            let time = time as f32 / FRAMES_PER_SECOND;
+            // Input of glTF sampler must strictly increase
+            if keys.last().is_none_or(|(last, _)| *last < time) {
+                keys.push((time, key));
+            }
+        }
+        if keys.is_empty() {
+            return None;
+        }
+        let interpolation = match track.interpolation_type {
+            InterpolationType::None => Interpolation::Step,
+            InterpolationType::Linear => Interpolation::Linear,
+            _ if linear_only => Interpolation::Linear,
+            _ => Interpolation::CubicSpline,
+        };
+        let shifted = |v: &[f32; N]| {
+            let mut result = *v;
+            result.iter_mut().zip(offset).for_each(|(r, o)| *r += o);
+            result
+        };
+        let mut values = vec![];
+        for (k, (time, key)) in keys.iter().enumerate() {
+            if interpolation != Interpolation::CubicSpline {
+                values.push(shifted(key.value()));
+                continue;
+            }
+            let bezier = track.interpolation_type == InterpolationType::Bezier;
+            let (value, in_tan, out_tan) = match key {
+                Track::Complex {
+                    value,
+                    in_tan,
+                    out_tan,
+                    ..
+                } => (*value, *in_tan, *out_tan),
+                Track::Linear { value, .. } if bezier => (*value, *value, *value),
+                // Hermite keys without tangents get secants of the segments
+                Track::Linear { value, .. } => {
+                    let prev = k.checked_sub(1).map_or(value, |p| keys[p].1.value());
+                    let next = keys.get(k + 1).map_or(value, |(_, n)| n.value());
+                    (
+                        *value,
+                        std::array::from_fn(|c| value[c] - prev[c]),
+                        std::array::from_fn(|c| next[c] - value[c]),
+                    )
+                }
+            };
+            // Tangents of MDX are derivatives by normalized time of the
+            // segment, glTF ones are derivatives by seconds.
+            let mut incoming = [0.0; N];
+            if k > 0 {
+                let dt = time - keys[k - 1].0;
+                for c in 0..N {
+                    incoming[c] = if bezier {
+                        3.0 * (value[c] - in_tan[c]) / dt
+                    } else {
+                        in_tan[c] / dt
+                    };
+                }
+            }
+            let mut outgoing = [0.0; N];
+            if let Some((next, _)) = keys.get(k + 1) {
+                let dt = next - time;
+                for c in 0..N {
+                    outgoing[c] = if bezier {
+                        3.0 * (out_tan[c] - value[c]) / dt
+                    } else {
+                        out_tan[c] / dt
+                    };
+                }
+            }
+            values.push(incoming);
+            values.push(shifted(&value));
+            values.push(outgoing);
+        }
+        let times: Vec<[f32; 1]> = keys.iter().map(|(time, _)| [*time]).collect();
+        let input = self.floats(&times, true, None);
+        let output = self.floats(&values, false, None);
+        Some(Sampler {
+            extensions: None,
+            extras: Default::default(),
+            input,
+            interpolation: Checked::Valid(interpolation),
+            output,
+        })
+    }
+
+    /// Step keys of the rest translation of the first joint at the start and
+    /// at the end of the sequence, `None` if there are no joints
+    fn rest_sampler(&mut self, sequence: &Sequence) -> Option<Sampler> {
+        let translation = *self.rest_translations.first()?;
+        let [start, end] = sequence.interval;
+        let mut times = vec![[0.0]];
+        if end > start {
+            times.push([(end - start) as f32 / FRAMES_PER_SECOND]);
+        }
+        let values = vec![translation; times.len()];
+        let input = self.floats(&times, true, None);
+        let output = self.floats(&values, false, None);
+        Some(Sampler {
+            extensions: None,
+            extras: Default::default(),
+            input,
+            interpolation: Checked::Valid(Interpolation::Step),
+            output,
+        })
+    }
+
+    /// Store vectors of floats, `bounds` adds minimum and maximum values that
+    /// are required for positions and animation inputs.
+    fn floats<const N: usize>(
+        &mut self,
+        values: &[[f32; N]],
+        bounds: bool,
+        target: Option<buffer::Target>,
+    ) -> Index<Accessor> {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let bounds = bounds.then(|| {
+            let mut min = [f32::MAX; N];
+            let mut max = [f32::MIN; N];
+            for value in values {
+                for c in 0..N {
+                    min[c] = min[c].min(value[c]);
+                    max[c] = max[c].max(value[c]);
+                }
+            }
+            (
+                json::Value::from(min.to_vec()),
+                json::Value::from(max.to_vec()),
+            )
+        });
+        self.accessor(
+            &bytes,
+            values.len(),
+            ComponentType::F32,
+            vector_type(N),
+            bounds,
+            target,
+        )
+    }
+
+    fn accessor(
+        &mut self,
+        bytes: &[u8],
+        count: usize,
+        component_type: ComponentType,
+        type_: Type,
+        bounds: Option<(json::Value, json::Value)>,
+        target: Option<buffer::Target>,
+    ) -> Index<Accessor> {
+        align(&mut self.buffer);
+        let offset = self.buffer.len();
+        self.buffer.extend_from_slice(bytes);
+        let view = self.document.push(View {
+            buffer: Index::new(0),
+            byte_length: USize64::from(bytes.len()),
+            byte_offset: Some(USize64::from(offset)),
+            byte_stride: None,
+            name: None,
+            target: target.map(Checked::Valid),
+            extensions: None,
+            extras: Default::default(),
+        });
+        let (min, max) = bounds.unzip();
+        self.document.push(Accessor {
+            buffer_view: Some(view),
+            byte_offset: None,
+            count: USize64::from(count),
+            component_type: Checked::Valid(GenericComponentType(component_type)),
+            extensions: None,
+            extras: Default::default(),
+            type_: Checked::Valid(type_),
+            min,
+            max,
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+}
@@ -0,0 +1,873 @@
+use super::error::Error;
+use super::{FRAMES_PER_SECOND, Z_UP_TO_Y_UP};
+use crate::math::{self, Mat4, Quat, Vec3};
+use crate::types::bone::Bone;
+use crate::types::chunk::utils::{Literal, Tag};
+use crate::types::chunk::*;
+use crate::types::extent::Extent;
+use crate::types::geoset::*;
+use crate::types::layer::{self, *};
+use crate::types::material::Material;
+use crate::types::node::*;
+use crate::types::sequence::Sequence;
+use crate::types::texture::Texture;
+use crate::types::tracks::*;
+use crate::types::MdxModel;
+use ::gltf::animation::util::ReadOutputs;
+use ::gltf::animation::{Interpolation, Property};
+use ::gltf::material::AlphaMode;
+use ::gltf::mesh::Mode;
+use ::gltf::texture::WrappingMode;
+use ::gltf::{buffer, image, Gltf};
+use log::*;
+use std::collections::{HashMap, HashSet};
+
+/// Pause between imported clips on the shared timeline, in frames
+const SEQUENCE_GAP: u32 = 1000;
+
+/// Texture of materials that have no base color texture
+const DEFAULT_TEXTURE: &str = "Textures\\white.blp";
+
+/// Blend time between sequences that the game uses for most of the models
+const BLEND_TIME: u32 = 150;
+
+/// Options that control building of models from glTF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Version of produced model. Vertices of models newer than 800 are
+    /// weighted with HD skin, older ones use matrix groups.
+    pub version: u32,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions { version: 1000 }
+    }
+}
+
+/// Build model from `.glb` file with default options
+pub fn import_gltf(slice: &[u8]) -> Result<MdxModel, Error> {
+    import_gltf_with(slice, &ImportOptions::default(), |_| None)
+}
+
+/// Build model from `.glb` or `.gltf` file. Buffers that are stored in
+/// separate files are requested from `load` by their URI.
+///
+/// Skin joints, meshes without skin and animated nodes become bones, their
+/// rest pose is baked into vertices and pivot points. Every animation clip
+/// becomes a sequence, clips follow each other on the timeline.
+pub fn import_gltf_with<F>(
+    slice: &[u8],
+    options: &ImportOptions,
+    mut load: F,
+) -> Result<MdxModel, Error>
+where
+    F: FnMut(&str) -> Option<Vec<u8>>,
+{
+    let gltf = Gltf::from_slice(slice).map_err(Error::Read)?;
+    let mut buffers = vec![];
+    for buffer in gltf.buffers() {
+        let data = match buffer.source() {
+            buffer::Source::Bin => gltf.blob.clone(),
+            buffer::Source::Uri(uri) => load(uri),
+        };
+        buffers.push(data.ok_or(Error::MissingBuffer(buffer.index()))?);
+    }
+    let mut importer = Importer {
+        gltf: &gltf,
+        buffers: &buffers,
+        version: options.version,
+        root: Mdlx::new(),
+        order: vec![],
+        rest: HashMap::new(),
+        parents: HashMap::new(),
+        bones: HashMap::new(),
+        textures: HashMap::new(),
+        materials: HashMap::new(),
+    };
+    importer.import()?;
+    Ok(MdxModel {
+        root: importer.root,
+    })
+}
+
+/// Literal cut to fit the fixed size with terminating zero
+fn literal<const N: usize>(content: &str) -> Literal<N> {
+    let mut end = content.len().min(N - 1);
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    Literal {
+        content: content[..end].to_owned(),
+    }
+}
+
+fn matrix(columns: [[f32; 4]; 4]) -> Mat4 {
+    std::array::from_fn(|i| columns[i / 4][i % 4])
+}
+
+/// Pose of glTF node in the model space when nothing is animated
+#[derive(Debug, Clone, Copy)]
+struct Rest {
+    world: Mat4,
+    rotation: Quat,
+}
+
+impl Rest {
+    /// Space of the scene root, it is turned from Y-up to Z-up
+    fn axes() -> Self {
+        let rotation = math::quat_conjugate(Z_UP_TO_Y_UP);
+        Rest {
+            world: math::from_trs([0.0; 3], rotation, [1.0; 3]),
+            rotation,
+        }
+    }
+}
+
+struct Importer<'a> {
+    gltf: &'a Gltf,
+    buffers: &'a [Vec<u8>],
+    version: u32,
+    root: Mdlx,
+    /// Nodes of the scene in depth-first order
+    order: Vec<::gltf::Node<'a>>,
+    rest: HashMap<usize, Rest>,
+    parents: HashMap<usize, usize>,
+    /// Object id of bone by index of glTF node
+    bones: HashMap<usize, u32>,
+    /// Texture id by index of glTF image
+    textures: HashMap<Option<usize>, u32>,
+    /// Material id by index of glTF material
+    materials: HashMap<Option<usize>, u32>,
+}
+
+impl<'a> Importer<'a> {
+    fn import(&mut self) -> Result<(), Error> {
+        self.root.vers = Some(Vers {
+            version: self.version,
+        });
+        let gltf = self.gltf;
+        let scene = gltf.default_scene().or_else(|| gltf.scenes().next());
+        self.traverse(scene.iter().flat_map(|s| s.nodes()).collect());
+        self.skeleton();
+        self.geosets()?;
+        self.animations();
+
+        let positions: Vec<Vec3> = self
+            .root
+            .geos
+            .iter()
+            .flat_map(|c| &c.geosets)
+            .flat_map(|g| g.vertex_positions.iter().copied())
+            .collect();
+        let extent = Extent::from_points(&positions);
+        let sequences = self.root.seqs.iter_mut().flat_map(|c| &mut c.sequences);
+        let mut sequence_count = 0;
+        for sequence in sequences {
+            sequence.extent = extent.clone();
+            sequence_count += 1;
+        }
+        for geoset in self.root.geos.iter_mut().flat_map(|c| &mut c.geosets) {
+            geoset.sequence_extents = vec![geoset.extent.clone(); sequence_count];
+        }
+        let name = scene.and_then(|s| s.name()).unwrap_or_default();
+        self.root.modl = Some(Modl {
+            name: literal(name),
+            animation_filename: literal(""),
+            extent,
+            blend_time: BLEND_TIME,
+        });
+        Ok(())
+    }
+
+    /// Find rest pose and parent of every node of the scene
+    fn traverse(&mut self, roots: Vec<::gltf::Node<'a>>) {
+        let mut stack: Vec<_> = roots.into_iter().rev().map(|n| (n, None)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            if self.rest.contains_key(&node.index()) {
+                warn!("Node {} is met twice in the scene", node.index());
+                continue;
+            }
+            let parent_rest = self.parent_rest(parent);
+            let (_, rotation, _) = node.transform().decomposed();
+            let rest = Rest {
+                world: math::mul(&parent_rest.world, &matrix(node.transform().matrix())),
+                rotation: math::quat_mul(parent_rest.rotation, rotation),
+            };
+            self.rest.insert(node.index(), rest);
+            if let Some(parent) = parent {
+                self.parents.insert(node.index(), parent);
+            }
+            for child in node.children().collect::<Vec<_>>().into_iter().rev() {
+                stack.push((child, Some(node.index())));
+            }
+            self.order.push(node);
+        }
+    }
+
+    fn parent_rest(&self, parent: Option<usize>) -> Rest {
+        parent
+            .and_then(|p| self.rest.get(&p))
+            .copied()
+            .unwrap_or_else(Rest::axes)
+    }
+
+    /// Create bones for skin joints, rigid meshes and animated nodes
+    fn skeleton(&mut self) {
+        let mut selected = HashSet::new();
+        for node in &self.order {
+            match node.skin() {
+                Some(skin) => selected.extend(skin.joints().map(|j| j.index())),
+                None if node.mesh().is_some() => {
+                    selected.insert(node.index());
+                }
+                None => (),
+            }
+        }
+        for animation in self.gltf.animations() {
+            for channel in animation.channels() {
+                let target = channel.target();
+                if target.property() != Property::MorphTargetWeights {
+                    selected.insert(target.node().index());
+                }
+            }
+        }
+        let mut bones = vec![];
+        let mut points = vec![];
+        for node in &self.order {
+            if !selected.contains(&node.index()) {
+                continue;
+            }
+            // Ancestors go first in depth-first order
+            let mut parent_id = u32::MAX;
+            let mut parent = self.parents.get(&node.index());
+            while let Some(index) = parent {
+                if let Some(id) = self.bones.get(index) {
+                    parent_id = *id;
+                    break;
+                }
+                parent = self.parents.get(index);
+            }
+            let object_id = bones.len() as u32;
+            self.bones.insert(node.index(), object_id);
+            let name = match node.name() {
+                Some(name) => name.to_owned(),
+                None => format!("Bone{}", node.index()),
+            };
+            bones.push(Bone {
+                node: Node {
+                    name: literal(&name),
+                    object_id,
+                    parent_id,
+                    flags: NodeFlags::BONE,
+                    kgtr: None,
+                    kgrt: None,
+                    kgsc: None,
+                    ordered: None,
+                },
+                geoset_id: u32::MAX,
+                geoset_animation_id: u32::MAX,
+            });
+            points.push(math::translation(&self.rest[&node.index()].world));
+        }
+        if !bones.is_empty() {
+            self.root.bone = Some(BoneChunk { bones });
+            self.root.pivt = Some(Pivt { points });
+        }
+    }
+
+    fn geosets(&mut self) -> Result<(), Error> {
+        for node in self.order.clone() {
+            let Some(mesh) = node.mesh() else {
+                continue;
+            };
+            for primitive in mesh.primitives() {
+                if let Some(geoset) = self.geoset(&node, &mesh, &primitive)? {
+                    self.root
+                        .geos
+                        .get_or_insert(Geos { geosets: vec![] })
+                        .geosets
+                        .push(geoset);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn geoset(
+        &mut self,
+        node: &::gltf::Node<'a>,
+        mesh: &::gltf::Mesh<'a>,
+        primitive: &::gltf::Primitive<'a>,
+    ) -> Result<Option<Geoset>, Error> {
+        let buffers = self.buffers;
+        let reader = primitive.reader(move |b| buffers.get(b.index()).map(Vec::as_slice));
+        let Some(positions) = reader.read_positions() else {
+            warn!(
+                "Primitive {} of mesh {} has no positions, skipping it",
+                primitive.index(),
+                mesh.index()
+            );
+            return Ok(None);
+        };
+        let positions: Vec<Vec3> = positions.collect();
+        let count = positions.len();
+        if count > usize::from(u16::MAX) + 1 {
+            return Err(Error::TooManyVertices {
+                mesh: mesh.index(),
+                primitive: primitive.index(),
+                count,
+            });
+        }
+        let face_type = match primitive.mode() {
+            Mode::Triangles => FaceTypeGroup::Triangles,
+            Mode::TriangleStrip => FaceTypeGroup::TriangleStrip,
+            Mode::TriangleFan => FaceTypeGroup::TriangleFan,
+            mode => {
+                warn!(
+                    "Primitive {} of mesh {} has mode {:?} without triangles, skipping it",
+                    primitive.index(),
+                    mesh.index(),
+                    mode
+                );
+                return Ok(None);
+            }
+        };
+        let faces: Vec<u16> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().map(|i| i as u16).collect(),
+            None => (0..count).map(|i| i as u16).collect(),
+        };
+
+        // Skinning of rest pose and node influences of every vertex
+        let rigid = self.rest[&node.index()].world;
+        let mut transforms = vec![rigid; count];
+        let mut influences = vec![vec![]; count];
+        match node.skin() {
+            Some(skin) => {
+                let skin_reader = skin.reader(move |b| buffers.get(b.index()).map(Vec::as_slice));
+                let inverse_binds: Vec<Mat4> = skin_reader
+                    .read_inverse_bind_matrices()
+                    .map(|m| m.map(matrix).collect())
+                    .unwrap_or_default();
+                let joints: Vec<Option<(u32, Mat4)>> = skin
+                    .joints()
+                    .enumerate()
+                    .map(|(i, joint)| {
+                        let id = *self.bones.get(&joint.index())?;
+                        let inverse_bind = inverse_binds.get(i).unwrap_or(&math::IDENTITY);
+                        Some((
+                            id,
+                            math::mul(&self.rest[&joint.index()].world, inverse_bind),
+                        ))
+                    })
+                    .collect();
+                let vertex_joints: Vec<[u16; 4]> = reader
+                    .read_joints(0)
+                    .map(|j| j.into_u16().collect())
+                    .unwrap_or_default();
+                let vertex_weights: Vec<[f32; 4]> = reader
+                    .read_weights(0)
+                    .map(|w| w.into_f32().collect())
+                    .unwrap_or_default();
+                let fallback = joints.iter().flatten().next().map(|(id, _)| *id);
+                for v in 0..count {
+                    let (Some(js), Some(ws)) = (vertex_joints.get(v), vertex_weights.get(v)) else {
+                        influences[v].extend(fallback.map(|id| (id, 1.0)));
+                        continue;
+                    };
+                    let mut transform = [0.0; 16];
+                    for (j, w) in js.iter().zip(ws).filter(|(_, w)| **w > 0.0) {
+                        if let Some(Some((id, joint_transform))) = joints.get(usize::from(*j)) {
+                            for (t, jt) in transform.iter_mut().zip(joint_transform) {
+                                *t += jt * w;
+                            }
+                            add_influence(&mut influences[v], *id, *w);
+                        }
+                    }
+                    if influences[v].is_empty() {
+                        influences[v].extend(fallback.map(|id| (id, 1.0)));
+                    } else {
+                        let total: f32 = influences[v].iter().map(|(_, w)| w).sum();
+                        transforms[v] = transform.map(|t| t / total);
+                    }
+                }
+            }
+            None => {
+                let id = self.bones[&node.index()];
+                influences.iter_mut().for_each(|i| i.push((id, 1.0)));
+            }
+        }
+
+        let mut geoset = Geoset {
+            vertex_positions: positions
+                .iter()
+                .zip(&transforms)
+                .map(|(p, t)| math::transform_point(t, *p))
+                .collect(),
+            vertex_normals: vec![],
+            face_type_groups: vec![face_type],
+            face_groups: vec![faces.len() as u32],
+            faces,
+            vertex_groups: vec![],
+            matrix_groups: vec![],
+            matrix_indicies: vec![],
+            material_id: self.material(primitive.material()),
+            selection_group: 0,
+            selection_flags: 0,
+            lod_extra: None,
+            extent: Extent::from_points(&[]),
+            sequence_extents: vec![],
+            tangents: None,
+            skin: None,
+            ordered: None,
+            texture_coordinate_sets: (0..)
+                .map_while(|set| reader.read_tex_coords(set))
+                .map(|coords| TextureCoordinateSet {
+                    texture_coordinates: coords.into_f32().collect(),
+                })
+                .collect(),
+        };
+        // Game draws only plain triangles
+        geoset.faces = geoset
+            .triangles()
+            .into_iter()
+            .filter(|t| t.iter().all(|i| usize::from(*i) < count))
+            .flatten()
+            .collect();
+        geoset.face_type_groups = vec![FaceTypeGroup::Triangles];
+        geoset.face_groups = vec![geoset.faces.len() as u32];
+        geoset.extent = Extent::from_points(&geoset.vertex_positions);
+        geoset.vertex_normals = match reader.read_normals() {
+            Some(normals) => normals
+                .zip(&transforms)
+                .map(|(n, t)| math::normalize(math::transform_vector(t, n)))
+                .collect(),
+            None => geoset.smooth_normals(),
+        };
+        if self.version > 800 {
+            geoset.lod_extra = Some(LodExtra {
+                lod: 0,
+                lod_name: literal(mesh.name().unwrap_or_default()),
+            });
+            geoset.tangents = reader.read_tangents().map(|tangents| Tangents {
+                tangents: tangents
+                    .zip(&transforms)
+                    .map(|([x, y, z, w], t)| {
+                        let [x, y, z] = math::normalize(math::transform_vector(t, [x, y, z]));
+                        [x, y, z, w]
+                    })
+                    .collect(),
+            });
+        }
+        let bones_error = |count| Error::TooManyBones {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            count,
+        };
+        for influence in influences.iter_mut() {
+            influence.sort_by(|a, b| b.1.total_cmp(&a.1));
+            influence.truncate(4);
+        }
+        if self.version > 800 {
+            bind_skin(&mut geoset, &influences).map_err(bones_error)?;
+        } else {
+            bind_matrix_groups(&mut geoset, &influences).map_err(bones_error)?;
+        }
+        Ok(Some(geoset))
+    }
+
+    fn material(&mut self, material: ::gltf::Material<'a>) -> u32 {
+        if let Some(id) = self.materials.get(&material.index()) {
+            return *id;
+        }
+        let pbr = material.pbr_metallic_roughness();
+        let info = pbr.base_color_texture();
+        let filter_mode = match material.alpha_mode() {
+            AlphaMode::Opaque => layer::FilterMode::None,
+            AlphaMode::Mask => layer::FilterMode::Transparent,
+            AlphaMode::Blend => layer::FilterMode::Blend,
+        };
+        let mut shading_flags = ShadingFlags::empty();
+        if material.double_sided() {
+            shading_flags |= ShadingFlags::TWO_SIDED;
+        }
+        let layer = Layer {
+            filter_mode,
+            shading_flags,
+            texture_id: self.texture(info.as_ref().map(|i| i.texture())),
+            texture_animation_id: u32::MAX,
+            coord_id: info.as_ref().map_or(0, |i| i.tex_coord()),
+            alpha: pbr.base_color_factor()[3],
+            extra: (self.version > 800).then_some(LayerExt {
+                emissive_gain: 1.0,
+                fresnel_color: [1.0; 3],
+                fresnel_opacity: 0.0,
+                fresnel_team_color: 0.0,
+            }),
+            kmtf: None,
+            kmta: None,
+            kmte: None,
+            kfc3: None,
+            kfca: None,
+            kftc: None,
+            ordered: None,
+        };
+        let materials = &mut self
+            .root
+            .mtls
+            .get_or_insert(Mtls { materials: vec![] })
+            .materials;
+        materials.push(Material {
+            priority_plane: 0,
+            flags: 0,
+            shader: (self.version > 800).then(|| literal("")),
+            layers: vec![layer],
+        });
+        let id = materials.len() as u32 - 1;
+        self.materials.insert(material.index(), id);
+        id
+    }
+
+    fn texture(&mut self, texture: Option<::gltf::Texture<'a>>) -> u32 {
+        let key = texture.as_ref().map(|t| t.source().index());
+        if let Some(id) = self.textures.get(&key) {
+            return *id;
+        }
+        let (file_name, flags) = match texture {
+            Some(texture) => {
+                let image = texture.source();
+                let file_name = match image.source() {
+                    image::Source::Uri { uri, .. } => uri.to_owned(),
+                    image::Source::View { .. } => match image.name() {
+                        Some(name) => name.to_owned(),
+                        None => format!("image{}", image.index()),
+                    },
+                };
+                let sampler = texture.sampler();
+                let mut flags = 0;
+                if sampler.wrap_s() == WrappingMode::Repeat {
+                    flags |= 1; // WrapWidth
+                }
+                if sampler.wrap_t() == WrappingMode::Repeat {
+                    flags |= 2; // WrapHeight
+                }
+                (file_name, flags)
+            }
+            None => (DEFAULT_TEXTURE.to_owned(), 0),
+        };
+        let textures = &mut self
+            .root
+            .texs
+            .get_or_insert(Texs { textures: vec![] })
+            .textures;
+        textures.push(Texture {
+            replaceable_id: 0,
+            file_name: literal(&file_name),
+            flags,
+        });
+        let id = textures.len() as u32 - 1;
+        self.textures.insert(key, id);
+        id
+    }
+
+    fn animations(&mut self) {
+        let buffers = self.buffers;
+        let mut curves: HashMap<usize, Curves> = HashMap::new();
+        let mut sequences = vec![];
+        let mut start = 0;
+        for animation in self.gltf.animations() {
+            let mut channels = vec![];
+            let mut duration: f32 = 0.0;
+            for channel in animation.channels() {
+                let reader = channel.reader(move |b| buffers.get(b.index()).map(Vec::as_slice));
+                let (Some(inputs), Some(outputs)) = (reader.read_inputs(), reader.read_outputs())
+                else {
+                    continue;
+                };
+                let times: Vec<f32> = inputs.collect();
+                duration = times.iter().copied().fold(duration, f32::max);
+                channels.push((channel, times, outputs));
+            }
+            for (channel, times, outputs) in channels {
+                let node = channel.target().node();
+                if !self.bones.contains_key(&node.index()) {
+                    continue;
+                }
+                let frames: Vec<i32> = times
+                    .iter()
+                    .map(|t| (start + (t * FRAMES_PER_SECOND).round() as u32) as i32)
+                    .collect();
+                let interpolation = channel.sampler().interpolation();
+                let (translation, rotation, scaling) = node.transform().decomposed();
+                let parent = self.parent_rest(self.parents.get(&node.index()).copied());
+                let curves = curves.entry(node.index()).or_default();
+                match outputs {
+                    // Translation relative to the rest pose in the parent space
+                    ReadOutputs::Translations(values) => {
+                        let values: Vec<Vec3> = values.collect();
+                        curves.translation.add(
+                            interpolation,
+                            true,
+                            (&frames, &times),
+                            &values,
+                            |v| math::transform_vector(&parent.world, math::sub(v, translation)),
+                            |v| math::transform_vector(&parent.world, v),
+                        );
+                    }
+                    // Rotation relative to the rest pose in the parent space
+                    ReadOutputs::Rotations(values) => {
+                        let values: Vec<Quat> = values.into_f32().collect();
+                        let rest = math::quat_conjugate(rotation);
+                        let outer = math::quat_conjugate(parent.rotation);
+                        let convert = |q| {
+                            let delta = math::quat_mul(q, rest);
+                            math::quat_mul(math::quat_mul(parent.rotation, delta), outer)
+                        };
+                        curves.rotation.add(
+                            interpolation,
+                            false,
+                            (&frames, &times),
+                            &values,
+                            convert,
+                            convert,
+                        );
+                    }
+                    // Scaling relative to the rest pose, axes are matched with
+                    // the parent space
+                    ReadOutputs::Scales(values) => {
+                        let values: Vec<Vec3> = values.collect();
+                        let axes = math::from_trs([0.0; 3], parent.rotation, [1.0; 3]);
+                        let convert = |v: Vec3| {
+                            let k: Vec<f32> = (0..3)
+                                .map(|j| {
+                                    if scaling[j] != 0.0 {
+                                        v[j] / scaling[j]
+                                    } else {
+                                        1.0
+                                    }
+                                })
+                                .collect();
+                            std::array::from_fn(|i| {
+                                (0..3).map(|j| axes[j * 4 + i].powi(2) * k[j]).sum()
+                            })
+                        };
+                        curves.scaling.add(
+                            interpolation,
+                            true,
+                            (&frames, &times),
+                            &values,
+                            convert,
+                            convert,
+                        );
+                    }
+                    ReadOutputs::MorphTargetWeights(_) => {
+                        warn!(
+                            "Morph target animation of node {} is not supported",
+                            node.index()
+                        );
+                    }
+                }
+            }
+            let end = start + (duration * FRAMES_PER_SECOND).round() as u32;
+            let name = match animation.name() {
+                Some(name) => name.to_owned(),
+                None => format!("Animation{}", animation.index()),
+            };
+            sequences.push(Sequence {
+                name: literal(&name),
+                interval: [start, end],
+                move_speed: 0.0,
+                flags: 0,
+                rarity: 0.0,
+                sync_point: 0,
+                extent: Extent::from_points(&[]),
+            });
+            start = end + SEQUENCE_GAP;
+        }
+        if !sequences.is_empty() {
+            self.root.seqs = Some(Seqs { sequences });
+        }
+        let bones = self.root.bone.iter_mut().flat_map(|c| &mut c.bones);
+        for bone in bones {
+            let node = &mut bone.node;
+            let index = self
+                .bones
+                .iter()
+                .find(|(_, id)| **id == node.object_id)
+                .map(|(index, _)| *index);
+            let Some(curves) = index.and_then(|i| curves.remove(&i)) else {
+                continue;
+            };
+            node.kgtr = curves.translation.finish(Kgtr::tag()).map(Kgtr);
+            node.kgrt = curves.rotation.finish(Kgrt::tag()).map(Kgrt);
+            node.kgsc = curves.scaling.finish(Kgsc::tag()).map(Kgsc);
+        }
+    }
+}
+
+/// Sum weights of the same node
+fn add_influence(influences: &mut Vec<(u32, f32)>, id: u32, weight: f32) {
+    match influences.iter_mut().find(|(i, _)| *i == id) {
+        Some((_, w)) => *w += weight,
+        None => influences.push((id, weight)),
+    }
+}
+
+/// Weight vertices with HD skin. Every node gets own matrix group, so models
+/// stay usable for readers that ignore the skin.
+fn bind_skin(geoset: &mut Geoset, influences: &[Vec<(u32, f32)>]) -> Result<(), usize> {
+    let mut skin = vec![];
+    for influence in influences {
+        let total: f32 = influence.iter().map(|(_, w)| w).sum();
+        let mut bones = [0; 4];
+        let mut weights = [0; 4];
+        for (k, (id, weight)) in influence.iter().enumerate() {
+            let index = match geoset.matrix_indicies.iter().position(|i| i == id) {
+                Some(index) => index,
+                None => {
+                    geoset.matrix_indicies.push(*id);
+                    geoset.matrix_indicies.len() - 1
+                }
+            };
+            bones[k] = index as u8;
+            weights[k] = (weight / total * 255.0).round() as u8;
+        }
+        // Weights must sum up to 255, the heaviest one takes the error
+        let sum: i32 = weights.iter().map(|w| i32::from(*w)).sum();
+        weights[0] = (i32::from(weights[0]) + 255 - sum).clamp(0, 255) as u8;
+        skin.extend(bones);
+        skin.extend(weights);
+        geoset.vertex_groups.push(bones[0]);
+    }
+    let count = geoset.matrix_indicies.len();
+    if count > 256 {
+        return Err(count);
+    }
+    geoset.matrix_groups = vec![1; count];
+    geoset.skin = Some(Skin { skin });
+    Ok(())
+}
+
+/// Weight vertices with matrix groups, nodes of a group have equal weights
+fn bind_matrix_groups(geoset: &mut Geoset, influences: &[Vec<(u32, f32)>]) -> Result<(), usize> {
+    let mut groups: Vec<Vec<u32>> = vec![];
+    for influence in influences {
+        let mut group: Vec<u32> = influence.iter().map(|(id, _)| *id).collect();
+        group.sort_unstable();
+        let index = match groups.iter().position(|g| *g == group) {
+            Some(index) => index,
+            None => {
+                groups.push(group);
+                groups.len() - 1
+            }
+        };
+        geoset.vertex_groups.push(index as u8);
+    }
+    if groups.len() > 256 {
+        return Err(groups.len());
+    }
+    geoset.matrix_groups = groups.iter().map(|g| g.len() as u32).collect();
+    geoset.matrix_indicies = groups.concat();
+    Ok(())
+}
+
+/// Animated properties of one node collected from all clips
+#[derive(Default)]
+struct Curves {
+    translation: Curve<3>,
+    rotation: Curve<4>,
+    scaling: Curve<3>,
+}
+
+#[derive(Default)]
+struct Curve<const N: usize> {
+    interpolations: Vec<InterpolationType>,
+    tracks: Vec<Track<[f32; N]>>,
+}
+
+impl<const N: usize> Curve<N> {
+    /// Append keys of glTF sampler. Cubic splines become Hermite tracks when
+    /// `smooth` is set, otherwise only values of the keys are taken.
+    fn add<V, T>(
+        &mut self,
+        interpolation: Interpolation,
+        smooth: bool,
+        (frames, times): (&[i32], &[f32]),
+        outputs: &[[f32; N]],
+        value: V,
+        tangent: T,
+    ) where
+        V: Fn([f32; N]) -> [f32; N],
+        T: Fn([f32; N]) -> [f32; N],
+    {
+        let cubic = interpolation == Interpolation::CubicSpline;
+        let interpolation_type = match interpolation {
+            Interpolation::Step => InterpolationType::None,
+            Interpolation::CubicSpline if smooth => InterpolationType::Hermite,
+            _ => InterpolationType::Linear,
+        };
+        self.interpolations.push(interpolation_type);
+        for (k, frame) in frames.iter().copied().enumerate() {
+            let key = if cubic {
+                outputs.get(k * 3..k * 3 + 3).map(|o| (o[0], o[1], o[2]))
+            } else {
+                outputs.get(k).map(|v| ([0.0; N], *v, [0.0; N]))
+            };
+            let Some((in_tan, v, out_tan)) = key else {
+                break;
+            };
+            // Keys of a track must strictly increase
+            if self.tracks.last().is_some_and(|t| t.frame() >= frame) {
+                continue;
+            }
+            if interpolation_type != InterpolationType::Hermite {
+                self.tracks.push(Track::Linear {
+                    frame,
+                    value: value(v),
+                });
+                continue;
+            }
+            // Tangents of glTF are derivatives by seconds, MDX ones are
+            // derivatives by normalized time of the segment.
+            let before = if k > 0 { times[k] - times[k - 1] } else { 0.0 };
+            let after = times.get(k + 1).map_or(0.0, |t| t - times[k]);
+            self.tracks.push(Track::Complex {
+                frame,
+                value: value(v),
+                in_tan: tangent(in_tan.map(|c| c * before)),
+                out_tan: tangent(out_tan.map(|c| c * after)),
+            });
+        }
+    }
+
+    /// Track of all clips. Clips with different interpolation fall back to
+    /// linear one.
+    fn finish(self, tag: Tag) -> Option<TrackChunk<[f32; N]>> {
+        let first = *self.interpolations.first()?;
+        if self.tracks.is_empty() {
+            return None;
+        }
+        let interpolation_type = if self.interpolations.iter().all(|i| *i == first) {
+            first
+        } else {
+            InterpolationType::Linear
+        };
+        let tracks = if interpolation_type == InterpolationType::Hermite {
+            self.tracks
+        } else {
+            self.tracks
+                .into_iter()
+                .map(|t| Track::Linear {
+                    frame: t.frame(),
+                    value: *t.value(),
+                })
+                .collect()
+        };
+        Some(TrackChunk {
+            tag,
+            interpolation_type,
+            global_sequence_id: u32::MAX,
+            tracks,
+        })
+    }
+}
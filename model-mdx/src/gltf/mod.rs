@@ -0,0 +1,19 @@
+//! Conversion of models to and from glTF 2.0. Geosets become primitives of a
+//! single skinned mesh, bones and helpers become joints and every sequence
+//! becomes an animation clip. Import goes the other way and lays clips out on
+//! a shared timeline.
+pub mod error;
+mod export;
+mod import;
+
+pub use error::Error;
+pub use export::{export_gltf, GltfAsset};
+pub use import::{import_gltf, import_gltf_with, ImportOptions};
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+/// Frames of MDX animations are milliseconds
+const FRAMES_PER_SECOND: f32 = 1000.0;
+
+/// Rotation from Z-up space of the game into Y-up space of glTF
+const Z_UP_TO_Y_UP: [f32; 4] = [-FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2];
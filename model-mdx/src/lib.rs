@@ -4,5 +4,24 @@ pub mod types;
 pub mod parser;
 /// Encodes in memory MDX into byte stream
 pub mod encoder;
+/// Reads and writes models in text MDL format
+pub mod mdl;
+/// Converts models between SD and HD versions
+pub mod convert;
+/// Vector, quaternion and matrix helpers
+mod math;
+/// Reads and writes geometry in Wavefront OBJ format
+pub mod obj;
+/// Evaluates keyframe tracks at a frame of a sequence
+pub mod sampling;
+/// Links model nodes into a tree and computes their transforms
+pub mod scene;
+/// Poses nodes and vertices at a frame of a sequence
+pub mod pose;
+/// Evaluates animated state of geosets and material layers
+pub mod state;
+/// Converts models to and from glTF 2.0
+#[cfg(feature = "gltf")]
+pub mod gltf;
 
 pub use types::*;
\ No newline at end of file
@@ -0,0 +1,210 @@
+//! Small set of vector, quaternion and matrix operations. Matrices are 4x4
+//! in column-major order, quaternions are stored as `[x, y, z, w]` like in
+//! the MDX tracks.
+
+pub type Vec3 = [f32; 3];
+pub type Quat = [f32; 4];
+pub type Mat4 = [f32; 16];
+
+pub const IDENTITY: Mat4 = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: Vec3, k: f32) -> Vec3 {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn length(a: Vec3) -> f32 {
+    (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt()
+}
+
+/// Unit vector of the same direction, zero vector stays zero
+pub fn normalize(a: Vec3) -> Vec3 {
+    let len = length(a);
+    if len > f32::EPSILON {
+        scale(a, 1.0 / len)
+    } else {
+        a
+    }
+}
+
+pub fn quat_mul(a: Quat, b: Quat) -> Quat {
+    [
+        a[3] * b[0] + a[0] * b[3] + a[1] * b[2] - a[2] * b[1],
+        a[3] * b[1] - a[0] * b[2] + a[1] * b[3] + a[2] * b[0],
+        a[3] * b[2] + a[0] * b[1] - a[1] * b[0] + a[2] * b[3],
+        a[3] * b[3] - a[0] * b[0] - a[1] * b[1] - a[2] * b[2],
+    ]
+}
+
+pub fn quat_conjugate(q: Quat) -> Quat {
+    [-q[0], -q[1], -q[2], q[3]]
+}
+
+/// Rotation around the unit axis by the angle in radians
+pub fn quat_from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+    let [x, y, z] = scale(axis, (angle / 2.0).sin());
+    [x, y, z, (angle / 2.0).cos()]
+}
+
+/// Rotation that takes unit axes X, Y and Z to the given orthonormal axes
+pub fn quat_from_axes(x: Vec3, y: Vec3, z: Vec3) -> Quat {
+    let trace = x[0] + y[1] + z[2];
+    if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            (y[2] - z[1]) / s,
+            (z[0] - x[2]) / s,
+            (x[1] - y[0]) / s,
+            s / 4.0,
+        ]
+    } else if x[0] > y[1] && x[0] > z[2] {
+        let s = (1.0 + x[0] - y[1] - z[2]).sqrt() * 2.0;
+        [
+            s / 4.0,
+            (y[0] + x[1]) / s,
+            (z[0] + x[2]) / s,
+            (y[2] - z[1]) / s,
+        ]
+    } else if y[1] > z[2] {
+        let s = (1.0 + y[1] - x[0] - z[2]).sqrt() * 2.0;
+        [
+            (y[0] + x[1]) / s,
+            s / 4.0,
+            (z[1] + y[2]) / s,
+            (z[0] - x[2]) / s,
+        ]
+    } else {
+        let s = (1.0 + z[2] - x[0] - y[1]).sqrt() * 2.0;
+        [
+            (z[0] + x[2]) / s,
+            (z[1] + y[2]) / s,
+            s / 4.0,
+            (x[1] - y[0]) / s,
+        ]
+    }
+}
+
+/// Spherical interpolation along the shortest arc
+pub fn quat_slerp(a: Quat, b: Quat, t: f32) -> Quat {
+    let mut dot: f32 = (0..4).map(|i| a[i] * b[i]).sum();
+    let mut b = b;
+    if dot < 0.0 {
+        dot = -dot;
+        b = b.map(|c| -c);
+    }
+    let (wa, wb) = if dot > 0.9995 {
+        // Nearly the same rotation, linear blend is precise enough
+        (1.0 - t, t)
+    } else {
+        let theta = dot.acos();
+        let sin = theta.sin();
+        (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+    };
+    let q: Quat = std::array::from_fn(|i| a[i] * wa + b[i] * wb);
+    let len = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if len > f32::EPSILON {
+        q.map(|c| c / len)
+    } else {
+        a
+    }
+}
+
+/// Spherical cubic interpolation between `a` and `b` with control
+/// quaternions `out_tan` of `a` and `in_tan` of `b`
+pub fn quat_squad(a: Quat, out_tan: Quat, in_tan: Quat, b: Quat, t: f32) -> Quat {
+    quat_slerp(
+        quat_slerp(a, b, t),
+        quat_slerp(out_tan, in_tan, t),
+        2.0 * t * (1.0 - t),
+    )
+}
+
+pub fn mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut result = [0.0; 16];
+    for column in 0..4 {
+        for row in 0..4 {
+            result[column * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[column * 4 + k]).sum();
+        }
+    }
+    result
+}
+
+/// Matrix that scales, then rotates and then translates
+pub fn from_trs(translation: Vec3, rotation: Quat, scaling: Vec3) -> Mat4 {
+    let [x, y, z, w] = rotation;
+    let [sx, sy, sz] = scaling;
+    [
+        (1.0 - 2.0 * (y * y + z * z)) * sx,
+        2.0 * (x * y + z * w) * sx,
+        2.0 * (x * z - y * w) * sx,
+        0.0,
+        2.0 * (x * y - z * w) * sy,
+        (1.0 - 2.0 * (x * x + z * z)) * sy,
+        2.0 * (y * z + x * w) * sy,
+        0.0,
+        2.0 * (x * z + y * w) * sz,
+        2.0 * (y * z - x * w) * sz,
+        (1.0 - 2.0 * (x * x + y * y)) * sz,
+        0.0,
+        translation[0],
+        translation[1],
+        translation[2],
+        1.0,
+    ]
+}
+
+/// Inverse of matrix that only rotates and translates
+pub fn inverse_rigid(m: &Mat4) -> Mat4 {
+    let mut result = IDENTITY;
+    for column in 0..3 {
+        for row in 0..3 {
+            result[column * 4 + row] = m[row * 4 + column];
+        }
+    }
+    let [x, y, z] = transform_vector(&result, translation(m));
+    result[12] = -x;
+    result[13] = -y;
+    result[14] = -z;
+    result
+}
+
+pub fn transform_point(m: &Mat4, p: Vec3) -> Vec3 {
+    add(transform_vector(m, p), translation(m))
+}
+
+/// Apply only linear part of the matrix, suitable for directions
+pub fn transform_vector(m: &Mat4, v: Vec3) -> Vec3 {
+    [
+        m[0] * v[0] + m[4] * v[1] + m[8] * v[2],
+        m[1] * v[0] + m[5] * v[1] + m[9] * v[2],
+        m[2] * v[0] + m[6] * v[1] + m[10] * v[2],
+    ]
+}
+
+pub fn translation(m: &Mat4) -> Vec3 {
+    [m[12], m[13], m[14]]
+}
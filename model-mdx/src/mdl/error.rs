@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+/// Errors that occur while reading MDL text
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("Line {line}: unexpected character {found:?}")]
+    UnexpectedChar { line: usize, found: char },
+    #[error("Line {line}: string is not closed")]
+    UnclosedString { line: usize },
+    #[error("Line {line}: block is not closed")]
+    UnclosedBlock { line: usize },
+    #[error("Line {line}: unexpected closing brace")]
+    UnexpectedClose { line: usize },
+    #[error("Line {line}: expected {expected}")]
+    Expected { line: usize, expected: &'static str },
+    #[error("Line {line}: cannot parse number {value:?}")]
+    InvalidNumber { line: usize, value: String },
+    #[error("Line {line}: unknown {kind} {value:?}")]
+    UnknownValue {
+        line: usize,
+        kind: &'static str,
+        value: String,
+    },
+}
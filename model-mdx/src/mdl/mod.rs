@@ -0,0 +1,12 @@
+//! Text MDL format that is used by model editors and exporters. The parser
+//! builds the same [MdxModel](crate::types::MdxModel) tree as the binary
+//! decoder and the writer emits the tree back as text.
+pub mod error;
+mod names;
+mod parser;
+mod syntax;
+mod writer;
+
+pub use error::Error;
+pub use parser::parse_mdl;
+pub use writer::write_mdl;
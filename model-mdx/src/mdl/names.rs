@@ -0,0 +1,125 @@
+//! Keywords of MDL format that map to enums and flags of the model
+use crate::types::emitter;
+use crate::types::geoset::FaceTypeGroup;
+use crate::types::layer::{self, ShadingFlags};
+use crate::types::light::LightType;
+use crate::types::node::NodeFlags;
+use crate::types::tracks::InterpolationType;
+
+pub(crate) const INTERPOLATION_TYPES: [(&str, InterpolationType); 4] = [
+    ("DontInterp", InterpolationType::None),
+    ("Linear", InterpolationType::Linear),
+    ("Hermite", InterpolationType::Hermite),
+    ("Bezier", InterpolationType::Bezier),
+];
+
+pub(crate) const LAYER_FILTER_MODES: [(&str, layer::FilterMode); 7] = [
+    ("None", layer::FilterMode::None),
+    ("Transparent", layer::FilterMode::Transparent),
+    ("Blend", layer::FilterMode::Blend),
+    ("Additive", layer::FilterMode::Additive),
+    ("AddAlpha", layer::FilterMode::AddAlpha),
+    ("Modulate", layer::FilterMode::Modulate),
+    ("Modulate2x", layer::FilterMode::Modulate2x),
+];
+
+pub(crate) const EMITTER_FILTER_MODES: [(&str, emitter::FilterMode); 5] = [
+    ("Blend", emitter::FilterMode::Blend),
+    ("Additive", emitter::FilterMode::Additive),
+    ("Modulate", emitter::FilterMode::Modulate),
+    ("Modulate2x", emitter::FilterMode::Modulate2x),
+    ("AlphaKey", emitter::FilterMode::AlphaKey),
+];
+
+pub(crate) const HEAD_TAIL: [(&str, emitter::HeadTail); 3] = [
+    ("Head", emitter::HeadTail::Head),
+    ("Tail", emitter::HeadTail::Tail),
+    ("Both", emitter::HeadTail::Both),
+];
+
+pub(crate) const LIGHT_TYPES: [(&str, LightType); 3] = [
+    ("Omni", LightType::Omni),
+    ("Directional", LightType::Directional),
+    ("Ambient", LightType::Ambient),
+];
+
+pub(crate) const FACE_TYPE_GROUPS: [(&str, FaceTypeGroup); 10] = [
+    ("Points", FaceTypeGroup::Points),
+    ("Lines", FaceTypeGroup::Lines),
+    ("LineLoop", FaceTypeGroup::LineLoop),
+    ("LineStrip", FaceTypeGroup::LineStrip),
+    ("Triangles", FaceTypeGroup::Triangles),
+    ("TriangleStrip", FaceTypeGroup::TriangleStrip),
+    ("TriangleFan", FaceTypeGroup::TriangleFan),
+    ("Quads", FaceTypeGroup::Quads),
+    ("QuadStrip", FaceTypeGroup::QuadStrip),
+    ("Polygons", FaceTypeGroup::Polygons),
+];
+
+pub(crate) const SHADING_FLAGS: [(&str, ShadingFlags); 6] = [
+    ("Unshaded", ShadingFlags::UNSHADED),
+    ("SphereEnvMap", ShadingFlags::SPHERE_ENV_MAP),
+    ("TwoSided", ShadingFlags::TWO_SIDED),
+    ("Unfogged", ShadingFlags::UNFOGGED),
+    ("NoDepthTest", ShadingFlags::NO_DEPTH_TEST),
+    ("NoDepthSet", ShadingFlags::NO_DEPTH_SET),
+];
+
+pub(crate) const MATERIAL_FLAGS: [(&str, u32); 5] = [
+    ("ConstantColor", 0x1),
+    ("TwoSided", 0x2),
+    ("SortPrimsNearZ", 0x8),
+    ("SortPrimsFarZ", 0x10),
+    ("FullResolution", 0x20),
+];
+
+pub(crate) const TEXTURE_FLAGS: [(&str, u32); 2] = [("WrapWidth", 0x1), ("WrapHeight", 0x2)];
+
+/// Flags that can be set on any node
+pub(crate) const NODE_FLAGS: [(&str, NodeFlags); 5] = [
+    ("Billboarded", NodeFlags::BILLBOARDED),
+    ("BillboardedLockX", NodeFlags::BILLBOARDED_LOCK_X),
+    ("BillboardedLockY", NodeFlags::BILLBOARDED_LOCK_Y),
+    ("BillboardedLockZ", NodeFlags::BILLBOARDED_LOCK_Z),
+    ("CameraAnchored", NodeFlags::CAMERA_ANCHORED),
+];
+
+/// Items of `DontInherit { .. }` block
+pub(crate) const DONT_INHERIT_FLAGS: [(&str, NodeFlags); 3] = [
+    ("Translation", NodeFlags::DONT_INHERIT_TRANSLATION),
+    ("Rotation", NodeFlags::DONT_INHERIT_ROTATION),
+    ("Scaling", NodeFlags::DONT_INHERIT_SCALING),
+];
+
+pub(crate) const PARTICLE_EMITTER_FLAGS: [(&str, NodeFlags); 2] = [
+    ("EmitterUsesMDL", NodeFlags::EMITTER_MOD_1),
+    ("EmitterUsesTGA", NodeFlags::EMITTER_MOD_2),
+];
+
+pub(crate) const PARTICLE_EMITTER2_FLAGS: [(&str, NodeFlags); 6] = [
+    ("Unshaded", NodeFlags::EMITTER_MOD_1),
+    ("SortPrimsFarZ", NodeFlags::EMITTER_MOD_2),
+    ("LineEmitter", NodeFlags::LINE_EMITTER),
+    ("Unfogged", NodeFlags::UNFOGGED),
+    ("ModelSpace", NodeFlags::MODEL_SPACE),
+    ("XYQuad", NodeFlags::XY_QUAD),
+];
+
+/// Find value by keyword ignoring case
+pub(crate) fn find<T: Copy>(table: &[(&str, T)], key: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| *value)
+}
+
+/// Find keyword of the value
+pub(crate) fn name_of<T: PartialEq>(
+    table: &[(&'static str, T)],
+    value: &T,
+) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(_, v)| v == value)
+        .map(|(name, _)| *name)
+}
@@ -0,0 +1,1420 @@
+use super::error::Error;
+use super::names::{self, find};
+use super::syntax::{parse_fields, Atom, Field, Scalar};
+use crate::types::animation::*;
+use crate::types::attachment::*;
+use crate::types::bone::Bone;
+use crate::types::camera::*;
+use crate::types::chunk::utils::Tag;
+use crate::types::chunk::*;
+use crate::types::collision::CollisionShape;
+use crate::types::emitter::{self, *};
+use crate::types::event::EventObject;
+use crate::types::extent::Extent;
+use crate::types::face::FaceEffect;
+use crate::types::geoset::*;
+use crate::types::layer::{self, *};
+use crate::types::light::*;
+use crate::types::material::Material;
+use crate::types::node::*;
+use crate::types::sequence::Sequence;
+use crate::types::sound::SoundTrack;
+use crate::types::texture::*;
+use crate::types::tracks::*;
+use crate::types::MdxModel;
+use log::*;
+
+/// Version of format that is assumed when MDL has no `Version` block
+const DEFAULT_VERSION: u32 = 800;
+
+/// Parse model from text MDL format
+pub fn parse_mdl(input: &str) -> Result<MdxModel, Error> {
+    let fields = parse_fields(input)?;
+    let mut builder = Builder::new();
+    // Layout of some objects depends on version, so it goes first
+    for field in fields.iter().filter(|f| f.is("Version")) {
+        builder.version(field)?;
+    }
+    for field in fields.iter().filter(|f| !f.is("Version")) {
+        builder.top_level(field)?;
+    }
+    Ok(MdxModel { root: builder.root })
+}
+
+struct Builder {
+    root: Mdlx,
+    version: u32,
+    next_object_id: u32,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Builder {
+            root: Mdlx::new(),
+            version: DEFAULT_VERSION,
+            next_object_id: 0,
+        }
+    }
+
+    fn version(&mut self, field: &Field) -> Result<(), Error> {
+        for item in field.body()? {
+            if item.is("FormatVersion") {
+                self.version = item.number()?;
+                self.root.vers = Some(Vers {
+                    version: self.version,
+                });
+            } else {
+                unknown_field(item, field);
+            }
+        }
+        Ok(())
+    }
+
+    fn top_level(&mut self, field: &Field) -> Result<(), Error> {
+        let root = &mut self.root;
+        match field.key() {
+            k if k.eq_ignore_ascii_case("Model") => root.modl = Some(parse_model(field)?),
+            k if k.eq_ignore_ascii_case("Sequences") => {
+                let sequences = parse_list(field, "Anim", parse_sequence)?;
+                root.seqs
+                    .get_or_insert(Seqs { sequences: vec![] })
+                    .sequences
+                    .extend(sequences);
+            }
+            k if k.eq_ignore_ascii_case("GlobalSequences") => {
+                let global_sequences = parse_list(field, "Duration", |f| f.number::<u32>())?;
+                root.glbs
+                    .get_or_insert(Glbs {
+                        global_sequences: vec![],
+                    })
+                    .global_sequences
+                    .extend(global_sequences);
+            }
+            k if k.eq_ignore_ascii_case("Textures") => {
+                let textures = parse_list(field, "Bitmap", parse_texture)?;
+                root.texs
+                    .get_or_insert(Texs { textures: vec![] })
+                    .textures
+                    .extend(textures);
+            }
+            k if k.eq_ignore_ascii_case("SoundTracks") => {
+                let sound_tracks = parse_list(field, "SoundTrack", parse_sound_track)?;
+                root.snds
+                    .get_or_insert(Snds {
+                        sound_tracks: vec![],
+                    })
+                    .sound_tracks
+                    .extend(sound_tracks);
+            }
+            k if k.eq_ignore_ascii_case("Materials") => {
+                let version = self.version;
+                let materials = parse_list(field, "Material", |f| parse_material(version, f))?;
+                root.mtls
+                    .get_or_insert(Mtls { materials: vec![] })
+                    .materials
+                    .extend(materials);
+            }
+            k if k.eq_ignore_ascii_case("TextureAnims") => {
+                let animations = parse_list(field, "TVertexAnim", parse_texture_animation)?;
+                root.txan
+                    .get_or_insert(Txan { animations: vec![] })
+                    .animations
+                    .extend(animations);
+            }
+            k if k.eq_ignore_ascii_case("Geoset") => {
+                let geoset = parse_geoset(self.version, field)?;
+                root.geos
+                    .get_or_insert(Geos { geosets: vec![] })
+                    .geosets
+                    .push(geoset);
+            }
+            k if k.eq_ignore_ascii_case("GeosetAnim") => {
+                let animation = parse_geoset_animation(field)?;
+                root.geoa
+                    .get_or_insert(Geoa { animations: vec![] })
+                    .animations
+                    .push(animation);
+            }
+            k if k.eq_ignore_ascii_case("Bone") => {
+                let bone = self.parse_bone(field)?;
+                let bones = &mut self
+                    .root
+                    .bone
+                    .get_or_insert(BoneChunk { bones: vec![] })
+                    .bones;
+                bones.push(bone);
+            }
+            k if k.eq_ignore_ascii_case("Light") => {
+                let light = self.parse_light(field)?;
+                let lights = &mut self.root.lite.get_or_insert(Lite { lights: vec![] }).lights;
+                lights.push(light);
+            }
+            k if k.eq_ignore_ascii_case("Helper") => {
+                let helper = self.parse_node(field, NodeFlags::HELPER, |_| Ok(false))?;
+                let helpers = &mut self
+                    .root
+                    .help
+                    .get_or_insert(Help { helpers: vec![] })
+                    .helpers;
+                helpers.push(helper);
+            }
+            k if k.eq_ignore_ascii_case("Attachment") => {
+                let attachment = self.parse_attachment(field)?;
+                let attachments = &mut self
+                    .root
+                    .atch
+                    .get_or_insert(Atch {
+                        attachments: vec![],
+                    })
+                    .attachments;
+                attachments.push(attachment);
+            }
+            k if k.eq_ignore_ascii_case("PivotPoints") => {
+                let points = vectors(field)?;
+                root.pivt
+                    .get_or_insert(Pivt { points: vec![] })
+                    .points
+                    .extend(points);
+            }
+            k if k.eq_ignore_ascii_case("ParticleEmitter") => {
+                let emitter = self.parse_particle_emitter(field)?;
+                let emitters = &mut self
+                    .root
+                    .prem
+                    .get_or_insert(Prem { emitters: vec![] })
+                    .emitters;
+                emitters.push(emitter);
+            }
+            k if k.eq_ignore_ascii_case("ParticleEmitter2") => {
+                let emitter = self.parse_particle_emitter2(field)?;
+                let emitters = &mut self
+                    .root
+                    .pre2
+                    .get_or_insert(Pre2 { emitters: vec![] })
+                    .emitters;
+                emitters.push(emitter);
+            }
+            k if k.eq_ignore_ascii_case("ParticleEmitterPopcorn") => {
+                let emitter = self.parse_popcorn_emitter(field)?;
+                let emitters = &mut self
+                    .root
+                    .corn
+                    .get_or_insert(Corn { emitters: vec![] })
+                    .emitters;
+                emitters.push(emitter);
+            }
+            k if k.eq_ignore_ascii_case("RibbonEmitter") => {
+                let emitter = self.parse_ribbon_emitter(field)?;
+                let emitters = &mut self
+                    .root
+                    .ribb
+                    .get_or_insert(Ribb { emitters: vec![] })
+                    .emitters;
+                emitters.push(emitter);
+            }
+            k if k.eq_ignore_ascii_case("EventObject") => {
+                let event = self.parse_event_object(field)?;
+                let events = &mut self.root.evts.get_or_insert(Evts { events: vec![] }).events;
+                events.push(event);
+            }
+            k if k.eq_ignore_ascii_case("Camera") => {
+                let camera = parse_camera(field)?;
+                root.cams
+                    .get_or_insert(Cams { cameras: vec![] })
+                    .cameras
+                    .push(camera);
+            }
+            k if k.eq_ignore_ascii_case("CollisionShape") => {
+                let shape = self.parse_collision_shape(field)?;
+                let shapes = &mut self.root.clid.get_or_insert(Clid { shapes: vec![] }).shapes;
+                shapes.push(shape);
+            }
+            k if k.eq_ignore_ascii_case("BindPose") => {
+                let mut matrices = vec![];
+                for item in field.body()? {
+                    if item.is("Matrices") {
+                        for matrix in item.body()? {
+                            matrices.push(matrix.vector()?);
+                        }
+                    } else {
+                        unknown_field(item, field);
+                    }
+                }
+                root.bpos
+                    .get_or_insert(Bpos { matrices: vec![] })
+                    .matrices
+                    .extend(matrices);
+            }
+            k if k.eq_ignore_ascii_case("FaceFX") => {
+                let mut effect = FaceEffect {
+                    target: literal(field.name().unwrap_or_default()),
+                    path: literal(""),
+                };
+                for item in field.body()? {
+                    if item.is("Path") {
+                        effect.path = literal(&item.string()?);
+                    } else {
+                        unknown_field(item, field);
+                    }
+                }
+                root.fafx
+                    .get_or_insert(Fafx {
+                        face_effects: vec![],
+                    })
+                    .face_effects
+                    .push(effect);
+            }
+            _ => warn!(
+                "Line {}: skipping unknown block {}",
+                field.line,
+                field.key()
+            ),
+        }
+        Ok(())
+    }
+
+    /// Parse fields that are common for all nodes, other fields are passed to
+    /// `other` handler that returns `false` for unknown fields.
+    fn parse_node<F>(
+        &mut self,
+        field: &Field,
+        flags: NodeFlags,
+        mut other: F,
+    ) -> Result<Node, Error>
+    where
+        F: FnMut(&Field) -> Result<bool, Error>,
+    {
+        let mut node = Node {
+            name: literal(field.name().unwrap_or_default()),
+            object_id: u32::MAX,
+            parent_id: u32::MAX,
+            flags,
+            kgtr: None,
+            kgrt: None,
+            kgsc: None,
+            ordered: None,
+        };
+        for item in field.body()? {
+            if item.is("ObjectId") {
+                node.object_id = item.number()?;
+            } else if item.is("Parent") {
+                node.parent_id = item.number()?;
+            } else if item.is("DontInherit") {
+                for flag in item.body()? {
+                    match find(&names::DONT_INHERIT_FLAGS, flag.key()) {
+                        Some(f) => node.flags |= f,
+                        None => unknown_field(flag, item),
+                    }
+                }
+            } else if let Some(flag) = node_flag(item.key()).filter(|_| item.head.len() == 1) {
+                node.flags |= flag;
+            } else if item.is("Translation") {
+                node.kgtr = Some(Kgtr(parse_track(item, Kgtr::tag())?));
+            } else if item.is("Rotation") {
+                node.kgrt = Some(Kgrt(parse_track(item, Kgrt::tag())?));
+            } else if item.is("Scaling") {
+                node.kgsc = Some(Kgsc(parse_track(item, Kgsc::tag())?));
+            } else if !other(item)? {
+                unknown_field(item, field);
+            }
+        }
+        // Some exporters don't write object ids at all
+        if node.object_id == u32::MAX {
+            node.object_id = self.next_object_id;
+        }
+        self.next_object_id = self.next_object_id.max(node.object_id.saturating_add(1));
+        Ok(node)
+    }
+
+    fn parse_bone(&mut self, field: &Field) -> Result<Bone, Error> {
+        let mut geoset_id = u32::MAX;
+        let mut geoset_animation_id = u32::MAX;
+        let node = self.parse_node(field, NodeFlags::BONE, |item| {
+            if item.is("GeosetId") {
+                geoset_id = optional_id(item, "Multiple")?;
+            } else if item.is("GeosetAnimId") {
+                geoset_animation_id = optional_id(item, "None")?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(Bone {
+            node,
+            geoset_id,
+            geoset_animation_id,
+        })
+    }
+
+    fn parse_light(&mut self, field: &Field) -> Result<Light, Error> {
+        let mut light_type = LightType::Omni;
+        let mut attenuation_start = 0.0;
+        let mut attenuation_end = 0.0;
+        let mut color = [1.0; 3];
+        let mut intensity = 0.0;
+        let mut ambient_color = [1.0; 3];
+        let mut ambient_intensity = 0.0;
+        let (mut klas, mut klae, mut klac, mut klai) = (None, None, None, None);
+        let (mut klbi, mut klbc, mut klav) = (None, None, None);
+        let node = self.parse_node(field, NodeFlags::LIGHT, |item| {
+            if let Some(t) = find(&names::LIGHT_TYPES, item.key()) {
+                light_type = t;
+            } else if item.is("AttenuationStart") {
+                animated(item, &mut attenuation_start, &mut klas, Klas::tag(), Klas)?;
+            } else if item.is("AttenuationEnd") {
+                animated(item, &mut attenuation_end, &mut klae, Klae::tag(), Klae)?;
+            } else if item.is("Color") {
+                animated(item, &mut color, &mut klac, Klac::tag(), Klac)?;
+            } else if item.is("Intensity") {
+                animated(item, &mut intensity, &mut klai, Klai::tag(), Klai)?;
+            } else if item.is("AmbIntensity") {
+                animated(item, &mut ambient_intensity, &mut klbi, Klbi::tag(), Klbi)?;
+            } else if item.is("AmbColor") {
+                animated(item, &mut ambient_color, &mut klbc, Klbc::tag(), Klbc)?;
+            } else if item.is("Visibility") {
+                klav = Some(Klav(parse_track(item, Klav::tag())?));
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(Light {
+            node,
+            light_type,
+            attenuation_start,
+            attenuation_end,
+            color,
+            intensity,
+            ambient_color,
+            ambient_intensity,
+            klas,
+            klae,
+            klac,
+            klai,
+            klbi,
+            klbc,
+            klav,
+            ordered: None,
+        })
+    }
+
+    fn parse_attachment(&mut self, field: &Field) -> Result<Attachment, Error> {
+        let mut path = String::new();
+        let mut attachment_id = 0;
+        let mut katv = None;
+        let node = self.parse_node(field, NodeFlags::ATTACHMENT, |item| {
+            if item.is("Path") {
+                path = item.string()?;
+            } else if item.is("AttachmentID") {
+                attachment_id = item.number()?;
+            } else if item.is("Visibility") {
+                katv = Some(Katv(parse_track(item, Katv::tag())?));
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(Attachment {
+            node,
+            path: literal(&path),
+            attachment_id,
+            katv,
+        })
+    }
+
+    fn parse_particle_emitter(&mut self, field: &Field) -> Result<ParticleEmitter, Error> {
+        let mut emission_rate = 0.0;
+        let mut gravity = 0.0;
+        let mut longitude = 0.0;
+        let mut latitude = 0.0;
+        let mut spawn_model_file_name = String::new();
+        let mut lifespan = 0.0;
+        let mut initial_velocity = 0.0;
+        let (mut kpee, mut kpeg, mut kpln, mut kplt) = (None, None, None, None);
+        let (mut kpel, mut kpes, mut kpev) = (None, None, None);
+        let node = self.parse_node(field, NodeFlags::PARTICLE_EMITTER, |item| {
+            if item.is("EmissionRate") {
+                animated(item, &mut emission_rate, &mut kpee, Kpee::tag(), Kpee)?;
+            } else if item.is("Gravity") {
+                animated(item, &mut gravity, &mut kpeg, Kpeg::tag(), Kpeg)?;
+            } else if item.is("Longitude") {
+                animated(item, &mut longitude, &mut kpln, Kpln::tag(), Kpln)?;
+            } else if item.is("Latitude") {
+                animated(item, &mut latitude, &mut kplt, Kplt::tag(), Kplt)?;
+            } else if item.is("Visibility") {
+                kpev = Some(Kpev(parse_track(item, Kpev::tag())?));
+            } else if item.is("Particle") {
+                for particle in item.body()? {
+                    if particle.is("LifeSpan") {
+                        animated(particle, &mut lifespan, &mut kpel, Kpel::tag(), Kpel)?;
+                    } else if particle.is("InitVelocity") {
+                        animated(
+                            particle,
+                            &mut initial_velocity,
+                            &mut kpes,
+                            Kpes::tag(),
+                            Kpes,
+                        )?;
+                    } else if particle.is("Path") {
+                        spawn_model_file_name = particle.string()?;
+                    } else {
+                        unknown_field(particle, item);
+                    }
+                }
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(ParticleEmitter {
+            node,
+            emission_rate,
+            gravity,
+            longitude,
+            latitude,
+            spawn_model_file_name: literal(&spawn_model_file_name),
+            lifespan,
+            initial_velocity,
+            kpee,
+            kpeg,
+            kpln,
+            kplt,
+            kpel,
+            kpes,
+            kpev,
+            ordered: None,
+        })
+    }
+
+    fn parse_particle_emitter2(&mut self, field: &Field) -> Result<ParticleEmitter2, Error> {
+        let mut speed = 0.0;
+        let mut variation = 0.0;
+        let mut latitude = 0.0;
+        let mut gravity = 0.0;
+        let mut lifespan = 0.0;
+        let mut emission_rate = 0.0;
+        let mut length = 0.0;
+        let mut width = 0.0;
+        let mut filter_mode = emitter::FilterMode::Blend;
+        let mut rows = 1;
+        let mut columns = 1;
+        let mut head_or_tail = HeadTail::Head;
+        let mut tail_length = 0.0;
+        let mut time = 0.0;
+        let mut segment_color = [[1.0; 3]; 3];
+        let mut segment_alpha = [255; 3];
+        let mut segment_scaling = [1.0; 3];
+        let mut head_interval = [0; 3];
+        let mut head_decay_interval = [0; 3];
+        let mut tail_interval = [0; 3];
+        let mut tail_decay_interval = [0; 3];
+        let mut texture_id = 0;
+        let mut squirt = 0;
+        let mut priority_plane = 0;
+        let mut replaceable_id = 0;
+        let (mut kp2s, mut kp2r, mut kp2l, mut kp2g) = (None, None, None, None);
+        let (mut kp2e, mut kp2n, mut kp2w, mut kp2v) = (None, None, None, None);
+        let node = self.parse_node(field, NodeFlags::PARTICLE_EMITTER, |item| {
+            if item.is("Speed") {
+                animated(item, &mut speed, &mut kp2s, Kp2s::tag(), Kp2s)?;
+            } else if item.is("Variation") {
+                animated(item, &mut variation, &mut kp2r, Kp2r::tag(), Kp2r)?;
+            } else if item.is("Latitude") {
+                animated(item, &mut latitude, &mut kp2l, Kp2l::tag(), Kp2l)?;
+            } else if item.is("Gravity") {
+                animated(item, &mut gravity, &mut kp2g, Kp2g::tag(), Kp2g)?;
+            } else if item.is("EmissionRate") {
+                animated(item, &mut emission_rate, &mut kp2e, Kp2e::tag(), Kp2e)?;
+            } else if item.is("Length") {
+                animated(item, &mut length, &mut kp2n, Kp2n::tag(), Kp2n)?;
+            } else if item.is("Width") {
+                animated(item, &mut width, &mut kp2w, Kp2w::tag(), Kp2w)?;
+            } else if item.is("Visibility") {
+                kp2v = Some(Kp2v(parse_track(item, Kp2v::tag())?));
+            } else if item.is("LifeSpan") {
+                lifespan = item.number()?;
+            } else if let Some(mode) = find(&names::EMITTER_FILTER_MODES, item.key()) {
+                filter_mode = mode;
+            } else if let Some(value) = find(&names::HEAD_TAIL, item.key()) {
+                head_or_tail = value;
+            } else if item.is("Rows") {
+                rows = item.number()?;
+            } else if item.is("Columns") {
+                columns = item.number()?;
+            } else if item.is("TailLength") {
+                tail_length = item.number()?;
+            } else if item.is("Time") {
+                time = item.number()?;
+            } else if item.is("SegmentColor") {
+                for (i, color) in item.body()?.iter().take(3).enumerate() {
+                    segment_color[i] = color.vector()?;
+                }
+            } else if item.is("Alpha") {
+                segment_alpha = item.vector()?;
+            } else if item.is("ParticleScaling") {
+                segment_scaling = item.vector()?;
+            } else if item.is("LifeSpanUVAnim") {
+                head_interval = item.vector()?;
+            } else if item.is("DecayUVAnim") {
+                head_decay_interval = item.vector()?;
+            } else if item.is("TailUVAnim") {
+                tail_interval = item.vector()?;
+            } else if item.is("TailDecayUVAnim") {
+                tail_decay_interval = item.vector()?;
+            } else if item.is("TextureID") {
+                texture_id = item.number()?;
+            } else if item.is("Squirt") {
+                squirt = 1;
+            } else if item.is("PriorityPlane") {
+                priority_plane = item.number()?;
+            } else if item.is("ReplaceableId") {
+                replaceable_id = item.number()?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(ParticleEmitter2 {
+            node,
+            speed,
+            variation,
+            latitude,
+            gravity,
+            lifespan,
+            emission_rate,
+            length,
+            width,
+            filter_mode,
+            rows,
+            columns,
+            head_or_tail,
+            tail_length,
+            time,
+            segment_color,
+            segment_alpha,
+            segment_scaling,
+            head_interval,
+            head_decay_interval,
+            tail_interval,
+            tail_decay_interval,
+            texture_id,
+            squirt,
+            priority_plane,
+            replaceable_id,
+            kp2s,
+            kp2r,
+            kp2l,
+            kp2g,
+            kp2e,
+            kp2n,
+            kp2w,
+            kp2v,
+            ordered: None,
+        })
+    }
+
+    fn parse_popcorn_emitter(&mut self, field: &Field) -> Result<CornEmitter, Error> {
+        let mut life_span = 0.0;
+        let mut emission_rate = 0.0;
+        let mut speed = 0.0;
+        let mut color = [1.0; 3];
+        let mut alpha = 1.0;
+        let mut replaceable_id = 0;
+        let mut path = String::new();
+        let mut flags = String::new();
+        let (mut kppa, mut kppc, mut kppe) = (None, None, None);
+        let (mut kppl, mut kpps, mut kppv) = (None, None, None);
+        let node = self.parse_node(field, NodeFlags::HELPER, |item| {
+            if item.is("LifeSpan") {
+                animated(item, &mut life_span, &mut kppl, Kppl::tag(), Kppl)?;
+            } else if item.is("EmissionRate") {
+                animated(item, &mut emission_rate, &mut kppe, Kppe::tag(), Kppe)?;
+            } else if item.is("Speed") {
+                animated(item, &mut speed, &mut kpps, Kpps::tag(), Kpps)?;
+            } else if item.is("Color") {
+                animated(item, &mut color, &mut kppc, Kppc::tag(), Kppc)?;
+            } else if item.is("Alpha") {
+                animated(item, &mut alpha, &mut kppa, Kppa::tag(), Kppa)?;
+            } else if item.is("Visibility") {
+                kppv = Some(Kppv(parse_track(item, Kppv::tag())?));
+            } else if item.is("ReplaceableId") {
+                replaceable_id = item.number()?;
+            } else if item.is("Path") {
+                path = item.string()?;
+            } else if item.is("AnimVisibilityGuide") {
+                flags = item.string()?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(CornEmitter {
+            node,
+            life_span,
+            emission_rate,
+            speed,
+            color: [color[0], color[1], color[2], alpha],
+            replaceable_id,
+            path: literal(&path),
+            flags: literal(&flags),
+            kppa,
+            kppc,
+            kppe,
+            kppl,
+            kpps,
+            kppv,
+            ordered: None,
+        })
+    }
+
+    fn parse_ribbon_emitter(&mut self, field: &Field) -> Result<RibbonEmitter, Error> {
+        let mut height_above = 0.0;
+        let mut height_below = 0.0;
+        let mut alpha = 1.0;
+        let mut color = [1.0; 3];
+        let mut lifespan = 0.0;
+        let mut texture_slot = 0;
+        let mut emission_rate = 0;
+        let mut rows = 1;
+        let mut columns = 1;
+        let mut material_id = 0;
+        let mut gravity = 0.0;
+        let (mut krha, mut krhb, mut kral) = (None, None, None);
+        let (mut krco, mut krtx, mut krvs) = (None, None, None);
+        let node = self.parse_node(field, NodeFlags::RIBBON_EMITTER, |item| {
+            if item.is("HeightAbove") {
+                animated(item, &mut height_above, &mut krha, Krha::tag(), Krha)?;
+            } else if item.is("HeightBelow") {
+                animated(item, &mut height_below, &mut krhb, Krhb::tag(), Krhb)?;
+            } else if item.is("Alpha") {
+                animated(item, &mut alpha, &mut kral, Kral::tag(), Kral)?;
+            } else if item.is("Color") {
+                animated(item, &mut color, &mut krco, Krco::tag(), Krco)?;
+            } else if item.is("TextureSlot") {
+                animated(item, &mut texture_slot, &mut krtx, Krtx::tag(), Krtx)?;
+            } else if item.is("Visibility") {
+                krvs = Some(Krvs(parse_track(item, Krvs::tag())?));
+            } else if item.is("EmissionRate") {
+                emission_rate = item.number()?;
+            } else if item.is("LifeSpan") {
+                lifespan = item.number()?;
+            } else if item.is("Gravity") {
+                gravity = item.number()?;
+            } else if item.is("Rows") {
+                rows = item.number()?;
+            } else if item.is("Columns") {
+                columns = item.number()?;
+            } else if item.is("MaterialID") {
+                material_id = item.number()?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(RibbonEmitter {
+            node,
+            height_above,
+            height_below,
+            alpha,
+            color,
+            lifespan,
+            texture_slot,
+            emission_rate,
+            rows,
+            columns,
+            material_id,
+            gravity,
+            krha,
+            krhb,
+            kral,
+            krco,
+            krtx,
+            krvs,
+            ordered: None,
+        })
+    }
+
+    fn parse_event_object(&mut self, field: &Field) -> Result<EventObject, Error> {
+        let mut global_sequence_id = u32::MAX;
+        let mut tracks = vec![];
+        let node = self.parse_node(field, NodeFlags::EVENT_OBJECT, |item| {
+            if item.is("EventTrack") {
+                tracks = item.list()?;
+            } else if item.is("GlobalSeqId") {
+                global_sequence_id = item.number()?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(EventObject {
+            node,
+            global_sequence_id,
+            tracks,
+        })
+    }
+
+    fn parse_collision_shape(&mut self, field: &Field) -> Result<CollisionShape, Error> {
+        let mut shape = String::from("box");
+        let mut vertices: Vec<[f32; 3]> = vec![];
+        let mut radius = 0.0;
+        let node = self.parse_node(field, NodeFlags::COLLISION_SHAPE, |item| {
+            if ["Box", "Plane", "Sphere", "Cylinder"]
+                .iter()
+                .any(|s| item.is(s))
+            {
+                shape = item.key().to_ascii_lowercase();
+            } else if item.is("Vertices") {
+                vertices = vectors(item)?;
+            } else if item.is("BoundsRadius") {
+                radius = item.number()?;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        let vertex = |i: usize| vertices.get(i).copied().unwrap_or_default();
+        let pair = [vertex(0), vertex(1)];
+        Ok(match shape.as_str() {
+            "plane" => CollisionShape::Plane {
+                node,
+                vertices: pair,
+            },
+            "sphere" => CollisionShape::Sphere {
+                node,
+                vertices: vertex(0),
+                radius,
+            },
+            "cylinder" => CollisionShape::Cylinder {
+                node,
+                vertices: pair,
+                radius,
+            },
+            _ => CollisionShape::Cube {
+                node,
+                vertices: pair,
+            },
+        })
+    }
+}
+
+fn unknown_field(field: &Field, parent: &Field) {
+    warn!(
+        "Line {}: skipping unknown field {:?} in {}",
+        field.line,
+        field.key(),
+        parent.key()
+    );
+}
+
+fn literal<const N: usize>(content: &str) -> Literal<N> {
+    Literal {
+        content: content.to_owned(),
+    }
+}
+
+fn empty_extent() -> Extent {
+    Extent {
+        bounds_radius: 0.0,
+        minimum: [0.0; 3],
+        maximum: [0.0; 3],
+    }
+}
+
+/// Handle extent fields, returns `false` if the field is not about extent
+fn parse_extent_field(extent: &mut Extent, field: &Field) -> Result<bool, Error> {
+    if field.is("MinimumExtent") {
+        extent.minimum = field.vector()?;
+    } else if field.is("MaximumExtent") {
+        extent.maximum = field.vector()?;
+    } else if field.is("BoundsRadius") {
+        extent.bounds_radius = field.number()?;
+    } else {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Parse blocks like `Textures 2 { Bitmap {..}, Bitmap {..} }`
+fn parse_list<T, F>(field: &Field, key: &str, mut parser: F) -> Result<Vec<T>, Error>
+where
+    F: FnMut(&Field) -> Result<T, Error>,
+{
+    let mut result = vec![];
+    for item in field.body()? {
+        if item.is(key) {
+            result.push(parser(item)?);
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(result)
+}
+
+/// Parse id that can be replaced with a word meaning "no id"
+fn optional_id(field: &Field, none: &str) -> Result<u32, Error> {
+    match field.word() {
+        Some(w) if w.eq_ignore_ascii_case(none) => Ok(u32::MAX),
+        _ => field.number(),
+    }
+}
+
+fn node_flag(key: &str) -> Option<NodeFlags> {
+    find(&names::NODE_FLAGS, key)
+        .or_else(|| find(&names::PARTICLE_EMITTER_FLAGS, key))
+        .or_else(|| find(&names::PARTICLE_EMITTER2_FLAGS, key))
+}
+
+fn layer_filter_mode(field: &Field) -> Result<layer::FilterMode, Error> {
+    let value = field.word().unwrap_or_default();
+    find(&names::LAYER_FILTER_MODES, value).ok_or_else(|| Error::UnknownValue {
+        line: field.line,
+        kind: "filter mode",
+        value: value.to_owned(),
+    })
+}
+
+/// Values that can be stored in tracks
+trait TrackValue: Clone + Sized {
+    fn from_field(field: &Field) -> Result<Self, Error>;
+}
+
+/// Scalar is the last number in the head, e.g. `0: 1.0` or `static Alpha 1.0`.
+/// Some exporters wrap scalars into braces.
+fn scalar_value<T: Scalar>(field: &Field) -> Result<T, Error> {
+    if field.body.is_some() {
+        if let Some(v) = field.list()?.first() {
+            return Ok(*v);
+        }
+    }
+    let value = field.numbers().last().ok_or(Error::Expected {
+        line: field.line,
+        expected: "number",
+    })?;
+    T::from_number(field.line, value)
+}
+
+impl TrackValue for f32 {
+    fn from_field(field: &Field) -> Result<Self, Error> {
+        scalar_value(field)
+    }
+}
+
+impl TrackValue for u32 {
+    fn from_field(field: &Field) -> Result<Self, Error> {
+        scalar_value(field)
+    }
+}
+
+impl<const N: usize> TrackValue for [f32; N] {
+    fn from_field(field: &Field) -> Result<Self, Error> {
+        field.vector()
+    }
+}
+
+/// Field is an animated block and not a static vector
+fn is_track(field: &Field) -> bool {
+    match &field.body {
+        Some(body) if !field.is_static() => {
+            body.is_empty()
+                || body.iter().any(|item| {
+                    item.head.contains(&Atom::Colon)
+                        || item.is("GlobalSeqId")
+                        || find(&names::INTERPOLATION_TYPES, item.key()).is_some()
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Parse animated block like:
+/// ```text
+/// Translation 2 {
+///     Hermite,
+///     GlobalSeqId 0,
+///     0: { 0, 0, 0 },
+///         InTan { 0, 0, 0 },
+///         OutTan { 0, 0, 0 },
+///     ...
+/// }
+/// ```
+fn parse_track<T: TrackValue>(field: &Field, tag: Tag) -> Result<TrackChunk<T>, Error> {
+    let mut interpolation = InterpolationType::None;
+    let mut global_sequence_id = u32::MAX;
+    let mut keys: Vec<(i32, T, Option<T>, Option<T>)> = vec![];
+    for item in field.body()? {
+        if item.head.contains(&Atom::Colon) {
+            let frame = item.number()?;
+            keys.push((frame, T::from_field(item)?, None, None));
+        } else if item.is("InTan") {
+            if let Some(key) = keys.last_mut() {
+                key.2 = Some(T::from_field(item)?);
+            }
+        } else if item.is("OutTan") {
+            if let Some(key) = keys.last_mut() {
+                key.3 = Some(T::from_field(item)?);
+            }
+        } else if item.is("GlobalSeqId") {
+            global_sequence_id = item.number()?;
+        } else if let Some(t) = find(&names::INTERPOLATION_TYPES, item.key()) {
+            interpolation = t;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    let tracks = keys
+        .into_iter()
+        .map(|(frame, value, in_tan, out_tan)| {
+            if interpolation > InterpolationType::Linear {
+                // Tangents are missing in files of some exporters
+                Track::Complex {
+                    frame,
+                    in_tan: in_tan.unwrap_or_else(|| value.clone()),
+                    out_tan: out_tan.unwrap_or_else(|| value.clone()),
+                    value,
+                }
+            } else {
+                Track::Linear { frame, value }
+            }
+        })
+        .collect();
+    Ok(TrackChunk {
+        tag,
+        interpolation_type: interpolation,
+        global_sequence_id,
+        tracks,
+    })
+}
+
+/// Parse field that is either `static Name value` or animated block
+fn animated<T, W, F>(
+    field: &Field,
+    value: &mut T,
+    track: &mut Option<W>,
+    tag: Tag,
+    wrap: F,
+) -> Result<(), Error>
+where
+    T: TrackValue,
+    F: FnOnce(TrackChunk<T>) -> W,
+{
+    if is_track(field) {
+        *track = Some(wrap(parse_track(field, tag)?));
+    } else {
+        *value = T::from_field(field)?;
+    }
+    Ok(())
+}
+
+fn parse_model(field: &Field) -> Result<Modl, Error> {
+    let mut modl = Modl {
+        name: literal(field.name().unwrap_or_default()),
+        animation_filename: literal(""),
+        extent: empty_extent(),
+        blend_time: 0,
+    };
+    for item in field.body()? {
+        if parse_extent_field(&mut modl.extent, item)? {
+            continue;
+        } else if item.is("BlendTime") {
+            modl.blend_time = item.number()?;
+        } else if item.is("AnimationFile") {
+            modl.animation_filename = literal(&item.string()?);
+        } else if !item.key().starts_with("Num") {
+            unknown_field(item, field);
+        }
+    }
+    Ok(modl)
+}
+
+fn parse_sequence(field: &Field) -> Result<Sequence, Error> {
+    let mut sequence = Sequence {
+        name: literal(field.name().unwrap_or_default()),
+        interval: [0, 0],
+        move_speed: 0.0,
+        flags: 0,
+        rarity: 0.0,
+        sync_point: 0,
+        extent: empty_extent(),
+    };
+    for item in field.body()? {
+        if parse_extent_field(&mut sequence.extent, item)? {
+            continue;
+        } else if item.is("Interval") {
+            sequence.interval = item.vector()?;
+        } else if item.is("NonLooping") {
+            sequence.flags = 1;
+        } else if item.is("MoveSpeed") {
+            sequence.move_speed = item.number()?;
+        } else if item.is("Rarity") {
+            sequence.rarity = item.number()?;
+        } else if item.is("SyncPoint") {
+            sequence.sync_point = item.number()?;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(sequence)
+}
+
+fn parse_texture(field: &Field) -> Result<Texture, Error> {
+    let mut texture = Texture {
+        replaceable_id: 0,
+        file_name: literal(""),
+        flags: 0,
+    };
+    for item in field.body()? {
+        if item.is("Image") {
+            texture.file_name = literal(item.name().unwrap_or_default());
+        } else if item.is("ReplaceableId") {
+            texture.replaceable_id = item.number()?;
+        } else if let Some(flag) = find(&names::TEXTURE_FLAGS, item.key()) {
+            texture.flags |= flag;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(texture)
+}
+
+fn parse_sound_track(field: &Field) -> Result<SoundTrack, Error> {
+    let mut sound = SoundTrack {
+        file_name: literal(field.name().unwrap_or_default()),
+        volume: 1.0,
+        pitch: 1.0,
+        flags: 0,
+    };
+    for item in field.body()? {
+        if item.is("Volume") {
+            sound.volume = item.number()?;
+        } else if item.is("Pitch") {
+            sound.pitch = item.number()?;
+        } else if item.is("Flags") {
+            sound.flags = item.number()?;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(sound)
+}
+
+fn parse_material(version: u32, field: &Field) -> Result<Material, Error> {
+    let mut material = Material {
+        priority_plane: 0,
+        flags: 0,
+        shader: (version > 800).then(|| literal("")),
+        layers: vec![],
+    };
+    for item in field.body()? {
+        if item.is("Layer") {
+            material.layers.push(parse_layer(version, item)?);
+        } else if item.is("PriorityPlane") {
+            material.priority_plane = item.number()?;
+        } else if item.is("Shader") {
+            if version > 800 {
+                material.shader = Some(literal(&item.string()?));
+            }
+        } else if let Some(flag) = find(&names::MATERIAL_FLAGS, item.key()) {
+            material.flags |= flag;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(material)
+}
+
+fn parse_layer(version: u32, field: &Field) -> Result<Layer, Error> {
+    let mut layer = Layer {
+        filter_mode: layer::FilterMode::None,
+        shading_flags: ShadingFlags::empty(),
+        texture_id: 0,
+        texture_animation_id: u32::MAX,
+        coord_id: 0,
+        alpha: 1.0,
+        extra: None,
+        kmtf: None,
+        kmta: None,
+        kmte: None,
+        kfc3: None,
+        kfca: None,
+        kftc: None,
+        ordered: None,
+    };
+    let mut extra = LayerExt {
+        emissive_gain: 1.0,
+        fresnel_color: [1.0; 3],
+        fresnel_opacity: 0.0,
+        fresnel_team_color: 0.0,
+    };
+    for item in field.body()? {
+        if item.is("FilterMode") {
+            layer.filter_mode = layer_filter_mode(item)?;
+        } else if let Some(flag) = find(&names::SHADING_FLAGS, item.key()) {
+            layer.shading_flags |= flag;
+        } else if item.is("TextureID") {
+            animated(
+                item,
+                &mut layer.texture_id,
+                &mut layer.kmtf,
+                Kmtf::tag(),
+                Kmtf,
+            )?;
+        } else if item.is("TVertexAnimId") {
+            layer.texture_animation_id = optional_id(item, "None")?;
+        } else if item.is("CoordId") {
+            layer.coord_id = item.number()?;
+        } else if item.is("Alpha") {
+            animated(item, &mut layer.alpha, &mut layer.kmta, Kmta::tag(), Kmta)?;
+        } else if item.is("EmissiveGain") {
+            animated(
+                item,
+                &mut extra.emissive_gain,
+                &mut layer.kmte,
+                Kmte::tag(),
+                Kmte,
+            )?;
+        } else if item.is("FresnelColor") {
+            animated(
+                item,
+                &mut extra.fresnel_color,
+                &mut layer.kfc3,
+                Kfc3::tag(),
+                Kfc3,
+            )?;
+        } else if item.is("FresnelOpacity") {
+            animated(
+                item,
+                &mut extra.fresnel_opacity,
+                &mut layer.kfca,
+                Kfca::tag(),
+                Kfca,
+            )?;
+        } else if item.is("FresnelTeamColor") {
+            animated(
+                item,
+                &mut extra.fresnel_team_color,
+                &mut layer.kftc,
+                Kftc::tag(),
+                Kftc,
+            )?;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    if version > 800 {
+        layer.extra = Some(extra);
+    } else {
+        layer.kmte = None;
+    }
+    if version <= 900 {
+        layer.kfc3 = None;
+        layer.kfca = None;
+        layer.kftc = None;
+    }
+    Ok(layer)
+}
+
+fn parse_texture_animation(field: &Field) -> Result<TextureAnimation, Error> {
+    let mut animation = TextureAnimation {
+        ktat: None,
+        ktar: None,
+        ktas: None,
+    };
+    for item in field.body()? {
+        if item.is("Translation") {
+            animation.ktat = Some(Ktat(parse_track(item, Ktat::tag())?));
+        } else if item.is("Rotation") {
+            animation.ktar = Some(Ktar(parse_track(item, Ktar::tag())?));
+        } else if item.is("Scaling") {
+            animation.ktas = Some(Ktas(parse_track(item, Ktas::tag())?));
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(animation)
+}
+
+fn vectors<const N: usize>(field: &Field) -> Result<Vec<[f32; N]>, Error> {
+    field.body()?.iter().map(|v| v.vector()).collect()
+}
+
+fn parse_geoset(version: u32, field: &Field) -> Result<Geoset, Error> {
+    let mut geoset = Geoset {
+        vertex_positions: vec![],
+        vertex_normals: vec![],
+        face_type_groups: vec![],
+        face_groups: vec![],
+        faces: vec![],
+        vertex_groups: vec![],
+        matrix_groups: vec![],
+        matrix_indicies: vec![],
+        material_id: 0,
+        selection_group: 0,
+        selection_flags: 0,
+        lod_extra: None,
+        extent: empty_extent(),
+        sequence_extents: vec![],
+        tangents: None,
+        skin: None,
+        ordered: None,
+        texture_coordinate_sets: vec![],
+    };
+    let mut lod = LodExtra {
+        lod: 0,
+        lod_name: literal(""),
+    };
+    for item in field.body()? {
+        if parse_extent_field(&mut geoset.extent, item)? {
+            continue;
+        } else if item.is("Vertices") {
+            geoset.vertex_positions = vectors(item)?;
+        } else if item.is("Normals") {
+            geoset.vertex_normals = vectors(item)?;
+        } else if item.is("TVertices") {
+            geoset.texture_coordinate_sets.push(TextureCoordinateSet {
+                texture_coordinates: vectors(item)?,
+            });
+        } else if item.is("VertexGroup") {
+            geoset.vertex_groups = item.list()?;
+        } else if item.is("Tangents") {
+            geoset.tangents = Some(Tangents {
+                tangents: vectors(item)?,
+            });
+        } else if item.is("SkinWeights") {
+            geoset.skin = Some(Skin { skin: item.list()? });
+        } else if item.is("Faces") {
+            for group in item.body()? {
+                let face_type = find(&names::FACE_TYPE_GROUPS, group.key()).ok_or_else(|| {
+                    Error::UnknownValue {
+                        line: group.line,
+                        kind: "face type",
+                        value: group.key().to_owned(),
+                    }
+                })?;
+                let faces: Vec<u16> = group.list()?;
+                geoset.face_type_groups.push(face_type);
+                geoset.face_groups.push(faces.len() as u32);
+                geoset.faces.extend(faces);
+            }
+        } else if item.is("Groups") {
+            for group in item.body()? {
+                if group.is("Matrices") {
+                    let indices: Vec<u32> = group.list()?;
+                    geoset.matrix_groups.push(indices.len() as u32);
+                    geoset.matrix_indicies.extend(indices);
+                } else {
+                    unknown_field(group, item);
+                }
+            }
+        } else if item.is("Anim") {
+            let mut extent = empty_extent();
+            for e in item.body()? {
+                if !parse_extent_field(&mut extent, e)? {
+                    unknown_field(e, item);
+                }
+            }
+            geoset.sequence_extents.push(extent);
+        } else if item.is("MaterialID") {
+            geoset.material_id = item.number()?;
+        } else if item.is("SelectionGroup") {
+            geoset.selection_group = item.number()?;
+        } else if item.is("Unselectable") {
+            geoset.selection_flags |= 4;
+        } else if item.is("LevelOfDetail") {
+            lod.lod = item.number()?;
+        } else if item.is("Name") {
+            lod.lod_name = literal(&item.string()?);
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    if version > 800 {
+        geoset.lod_extra = Some(lod);
+    } else {
+        geoset.tangents = None;
+        geoset.skin = None;
+    }
+    Ok(geoset)
+}
+
+fn parse_geoset_animation(field: &Field) -> Result<GeosetAnimation, Error> {
+    let mut animation = GeosetAnimation {
+        alpha: 1.0,
+        flags: 0,
+        color: [1.0; 3],
+        geoset_id: u32::MAX,
+        kgao: None,
+        kgac: None,
+        ordered: None,
+    };
+    for item in field.body()? {
+        if item.is("DropShadow") {
+            animation.flags |= GEOSET_ANIMATION_DROP_SHADOW;
+        } else if item.is("Alpha") {
+            animated(
+                item,
+                &mut animation.alpha,
+                &mut animation.kgao,
+                Kgao::tag(),
+                Kgao,
+            )?;
+        } else if item.is("Color") {
+            animation.flags |= GEOSET_ANIMATION_USE_COLOR;
+            animated(
+                item,
+                &mut animation.color,
+                &mut animation.kgac,
+                Kgac::tag(),
+                Kgac,
+            )?;
+        } else if item.is("GeosetId") {
+            animation.geoset_id = optional_id(item, "None")?;
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(animation)
+}
+
+fn parse_camera(field: &Field) -> Result<Camera, Error> {
+    let mut camera = Camera {
+        name: literal(field.name().unwrap_or_default()),
+        position: [0.0; 3],
+        field_of_view: 0.0,
+        far_clipping_plane: 0.0,
+        near_clipping_plane: 0.0,
+        target_position: [0.0; 3],
+        kctr: None,
+        kttr: None,
+        kcrl: None,
+        ordered: None,
+    };
+    for item in field.body()? {
+        if item.is("Position") {
+            camera.position = item.vector()?;
+        } else if item.is("Translation") {
+            camera.kctr = Some(Kctr(parse_track(item, Kctr::tag())?));
+        } else if item.is("Rotation") {
+            camera.kcrl = Some(Kcrl(parse_track(item, Kcrl::tag())?));
+        } else if item.is("FieldOfView") {
+            camera.field_of_view = item.number()?;
+        } else if item.is("FarClip") {
+            camera.far_clipping_plane = item.number()?;
+        } else if item.is("NearClip") {
+            camera.near_clipping_plane = item.number()?;
+        } else if item.is("Target") {
+            for target in item.body()? {
+                if target.is("Position") {
+                    camera.target_position = target.vector()?;
+                } else if target.is("Translation") {
+                    camera.kttr = Some(Kttr(parse_track(target, Kttr::tag())?));
+                } else {
+                    unknown_field(target, item);
+                }
+            }
+        } else {
+            unknown_field(item, field);
+        }
+    }
+    Ok(camera)
+}
@@ -0,0 +1,362 @@
+use super::error::Error;
+
+/// Value in the head of [Field]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Atom {
+    Word(String),
+    Number(String),
+    Str(String),
+    Colon,
+}
+
+/// Statement of MDL file: keywords and values that end with comma, line end
+/// or a block in braces. Vectors like `{ 1, 2, 3 }` are fields with the
+/// empty head and a block of numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Field {
+    pub line: usize,
+    pub head: Vec<Atom>,
+    pub body: Option<Vec<Field>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Atom(Atom),
+    Open,
+    Close,
+    Comma,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lexeme {
+    token: Token,
+    line: usize,
+    /// Token is the first on its line
+    newline: bool,
+}
+
+/// Split MDL text into tree of fields
+pub(crate) fn parse_fields(input: &str) -> Result<Vec<Field>, Error> {
+    let tokens = tokenize(input)?;
+    let mut pos = 0;
+    parse_block(&tokens, &mut pos, None)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Lexeme>, Error> {
+    let mut tokens = vec![];
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+    let mut line = 1;
+    let mut newline = true;
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                newline = true;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '{' => Token::Open,
+            '}' => Token::Close,
+            ',' => Token::Comma,
+            ':' => Token::Atom(Atom::Colon),
+            '"' => {
+                let start = line;
+                let mut content = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            content.push(c);
+                        }
+                        None => return Err(Error::UnclosedString { line: start }),
+                    }
+                }
+                Token::Atom(Atom::Str(content))
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let mut content = c.to_string();
+                while let Some(c) = chars
+                    .next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '#' | '-' | '+'))
+                {
+                    content.push(c);
+                }
+                Token::Atom(Atom::Number(content))
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut content = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    content.push(c);
+                }
+                Token::Atom(Atom::Word(content))
+            }
+            found => return Err(Error::UnexpectedChar { line, found }),
+        };
+        tokens.push(Lexeme {
+            token,
+            line,
+            newline,
+        });
+        newline = false;
+    }
+    Ok(tokens)
+}
+
+fn parse_block(
+    tokens: &[Lexeme],
+    pos: &mut usize,
+    open_line: Option<usize>,
+) -> Result<Vec<Field>, Error> {
+    let mut fields = vec![];
+    loop {
+        match tokens.get(*pos) {
+            None => {
+                return match open_line {
+                    Some(line) => Err(Error::UnclosedBlock { line }),
+                    None => Ok(fields),
+                }
+            }
+            Some(Lexeme {
+                token: Token::Comma,
+                ..
+            }) => *pos += 1,
+            Some(Lexeme {
+                token: Token::Close,
+                line,
+                ..
+            }) => {
+                *pos += 1;
+                return match open_line {
+                    Some(_) => Ok(fields),
+                    None => Err(Error::UnexpectedClose { line: *line }),
+                };
+            }
+            Some(_) => fields.push(parse_field(tokens, pos)?),
+        }
+    }
+}
+
+fn parse_field(tokens: &[Lexeme], pos: &mut usize) -> Result<Field, Error> {
+    let line = tokens[*pos].line;
+    let mut head = vec![];
+    while let Some(lexeme) = tokens.get(*pos) {
+        match &lexeme.token {
+            Token::Comma | Token::Close => break,
+            Token::Open => {
+                *pos += 1;
+                let body = parse_block(tokens, pos, Some(lexeme.line))?;
+                return Ok(Field {
+                    line,
+                    head,
+                    body: Some(body),
+                });
+            }
+            // Some exporters miss commas at the end of lines
+            Token::Atom(_) if lexeme.newline && !head.is_empty() => break,
+            Token::Atom(atom) => {
+                head.push(atom.clone());
+                *pos += 1;
+            }
+        }
+    }
+    Ok(Field {
+        line,
+        head,
+        body: None,
+    })
+}
+
+/// Numbers that can be read from MDL values
+pub(crate) trait Scalar: Sized + Copy {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error>;
+}
+
+/// Parse number including the `1.#INF` and `-1.#IND` forms that are
+/// written by MSVC runtime.
+fn parse_f64(line: usize, value: &str) -> Result<f64, Error> {
+    if let Ok(v) = value.parse::<f64>() {
+        return Ok(v);
+    }
+    let upper = value.to_ascii_uppercase();
+    let negative = upper.starts_with('-');
+    if upper.contains("#INF") {
+        Ok(if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        })
+    } else if upper.contains("#IND") || upper.contains("NAN") {
+        Ok(f64::NAN)
+    } else {
+        Err(Error::InvalidNumber {
+            line,
+            value: value.to_owned(),
+        })
+    }
+}
+
+impl Scalar for f32 {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error> {
+        // Parsing directly into `f32` avoids double rounding, so values
+        // written by the encoder are read back exactly
+        match value.parse::<f32>() {
+            Ok(v) => Ok(v),
+            Err(_) => parse_f64(line, value).map(|v| v as f32),
+        }
+    }
+}
+
+/// Integers are sometimes written as floats, e.g. `Rows 1.0`.
+fn parse_integer(line: usize, value: &str, min: f64, max: f64) -> Result<f64, Error> {
+    let v = match value.parse::<i64>() {
+        Ok(v) => v as f64,
+        Err(_) => parse_f64(line, value)?.round(),
+    };
+    if v.is_finite() && v >= min && v <= max {
+        Ok(v)
+    } else {
+        Err(Error::InvalidNumber {
+            line,
+            value: value.to_owned(),
+        })
+    }
+}
+
+impl Scalar for u32 {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error> {
+        // `-1` is used for missing references like `Parent -1`
+        let v = parse_integer(line, value, i32::MIN as f64, u32::MAX as f64)?;
+        Ok(if v < 0.0 { v as i32 as u32 } else { v as u32 })
+    }
+}
+
+impl Scalar for i32 {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error> {
+        parse_integer(line, value, i32::MIN as f64, i32::MAX as f64).map(|v| v as i32)
+    }
+}
+
+impl Scalar for u8 {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error> {
+        parse_integer(line, value, 0.0, u8::MAX as f64).map(|v| v as u8)
+    }
+}
+
+impl Scalar for u16 {
+    fn from_number(line: usize, value: &str) -> Result<Self, Error> {
+        parse_integer(line, value, 0.0, u16::MAX as f64).map(|v| v as u16)
+    }
+}
+
+impl Field {
+    /// Field starts with `static` keyword
+    pub fn is_static(&self) -> bool {
+        matches!(self.head.first(), Some(Atom::Word(w)) if w.eq_ignore_ascii_case("static"))
+    }
+
+    /// Keyword of the field without `static` prefix
+    pub fn key(&self) -> &str {
+        let skip = usize::from(self.is_static());
+        match self.head.get(skip) {
+            Some(Atom::Word(w)) => w,
+            _ => "",
+        }
+    }
+
+    /// Check keyword ignoring case
+    pub fn is(&self, key: &str) -> bool {
+        self.key().eq_ignore_ascii_case(key)
+    }
+
+    /// First string value of the field, e.g. name of a node
+    pub fn name(&self) -> Option<&str> {
+        self.head.iter().find_map(|a| match a {
+            Atom::Str(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// String value of the field or error
+    pub fn string(&self) -> Result<String, Error> {
+        self.name().map(|s| s.to_owned()).ok_or(Error::Expected {
+            line: self.line,
+            expected: "string",
+        })
+    }
+
+    /// Word that follows the keyword, e.g. `Transparent` in `FilterMode Transparent`
+    pub fn word(&self) -> Option<&str> {
+        self.head
+            .iter()
+            .skip(usize::from(self.is_static()) + 1)
+            .find_map(|a| match a {
+                Atom::Word(w) => Some(w.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Numbers in the head of the field
+    pub fn numbers(&self) -> impl Iterator<Item = &str> {
+        self.head.iter().filter_map(|a| match a {
+            Atom::Number(n) => Some(n.as_str()),
+            _ => None,
+        })
+    }
+
+    /// First number in the head of the field
+    pub fn number<T: Scalar>(&self) -> Result<T, Error> {
+        let value = self.numbers().next().ok_or(Error::Expected {
+            line: self.line,
+            expected: "number",
+        })?;
+        T::from_number(self.line, value)
+    }
+
+    /// Fields of the block or error if there is no block
+    pub fn body(&self) -> Result<&[Field], Error> {
+        self.body.as_deref().ok_or(Error::Expected {
+            line: self.line,
+            expected: "block",
+        })
+    }
+
+    /// All numbers from the block including nested ones, e.g. `{ 1, 2, 3 }`
+    /// or `{ { 0, 1, 2 }, { 2, 3, 0 } }`
+    pub fn list<T: Scalar>(&self) -> Result<Vec<T>, Error> {
+        let mut result = vec![];
+        self.collect_numbers(&mut result)?;
+        Ok(result)
+    }
+
+    fn collect_numbers<T: Scalar>(&self, result: &mut Vec<T>) -> Result<(), Error> {
+        for field in self.body()? {
+            for n in field.numbers() {
+                result.push(T::from_number(field.line, n)?);
+            }
+            if field.body.is_some() {
+                field.collect_numbers(result)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fixed size vector from the block
+    pub fn vector<T: Scalar + Default, const N: usize>(&self) -> Result<[T; N], Error> {
+        let values = self.list()?;
+        if values.len() < N {
+            return Err(Error::Expected {
+                line: self.line,
+                expected: "more vector components",
+            });
+        }
+        let mut result = [T::default(); N];
+        result.copy_from_slice(&values[..N]);
+        Ok(result)
+    }
+}
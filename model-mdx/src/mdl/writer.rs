@@ -0,0 +1,791 @@
+use super::names::{self, name_of};
+use crate::types::animation::{GEOSET_ANIMATION_DROP_SHADOW, GEOSET_ANIMATION_USE_COLOR};
+use crate::types::chunk::*;
+use crate::types::collision::CollisionShape;
+use crate::types::emitter::*;
+use crate::types::extent::Extent;
+use crate::types::geoset::*;
+use crate::types::layer::Layer;
+use crate::types::light::Light;
+use crate::types::material::Material;
+use crate::types::node::*;
+use crate::types::tracks::*;
+use crate::types::MdxModel;
+use log::*;
+
+/// Write model in text MDL format. Blocks always go in the canonical order
+/// of the format, so the same model gives the same text regardless of the
+/// chunk order in the source file.
+///
+/// Animated values are written both as `static` value and as a track, so
+/// parsing the text back gives the same model.
+pub fn write_mdl(model: &MdxModel) -> String {
+    let mut writer = Writer::default();
+    writer.model(&model.root);
+    writer.out
+}
+
+/// Values that can be written into MDL text
+trait Value {
+    fn mdl(&self) -> String;
+}
+
+impl Value for f32 {
+    /// Shortest representation that reads back into the same value. Special
+    /// values use the MSVC runtime forms that are understood by other tools.
+    fn mdl(&self) -> String {
+        if self.is_nan() {
+            "-1.#IND".to_owned()
+        } else if self.is_infinite() {
+            if *self > 0.0 { "1.#INF" } else { "-1.#INF" }.to_owned()
+        } else {
+            format!("{}", self)
+        }
+    }
+}
+
+impl Value for u32 {
+    fn mdl(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl Value for u8 {
+    fn mdl(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl Value for u16 {
+    fn mdl(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl<T: Value, const N: usize> Value for [T; N] {
+    fn mdl(&self) -> String {
+        let items: Vec<String> = self.iter().map(|v| v.mdl()).collect();
+        format!("{{ {} }}", items.join(", "))
+    }
+}
+
+fn quoted(content: &str) -> String {
+    format!("\"{}\"", content)
+}
+
+/// Id that is written as a word when it is missing
+fn optional_id(id: u32, none: &str) -> String {
+    if id == u32::MAX {
+        none.to_owned()
+    } else {
+        id.mdl()
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    indent: usize,
+}
+
+impl Writer {
+    fn line(&mut self, content: &str) {
+        for _ in 0..self.indent {
+            self.out.push('\t');
+        }
+        self.out.push_str(content);
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, header: &str) {
+        self.line(&format!("{} {{", header));
+        self.indent += 1;
+    }
+
+    fn close(&mut self) {
+        self.indent = self.indent.saturating_sub(1);
+        self.line("}");
+    }
+
+    /// Line like `Key value,`
+    fn field<T: Value>(&mut self, key: &str, value: &T) {
+        self.line(&format!("{} {},", key, value.mdl()));
+    }
+
+    /// Line with the keyword only like `Unshaded,`
+    fn flag(&mut self, key: &str) {
+        self.line(&format!("{},", key));
+    }
+
+    /// List of values like `Vertices 2 { { 0, 0, 0 }, { 1, 1, 1 }, }`
+    fn values<T: Value>(&mut self, header: &str, values: &[T]) {
+        self.open(&format!("{} {}", header, values.len()));
+        for v in values {
+            self.line(&format!("{},", v.mdl()));
+        }
+        self.close();
+    }
+
+    /// Static value that is followed by track if the value is animated
+    fn animated<T: Value + Clone>(&mut self, key: &str, value: &T, track: Option<&TrackChunk<T>>) {
+        self.field(&format!("static {}", key), value);
+        if let Some(track) = track {
+            self.track(key, track);
+        }
+    }
+
+    fn track<T: Value + Clone>(&mut self, key: &str, track: &TrackChunk<T>) {
+        self.open(&format!("{} {}", key, track.tracks.len()));
+        let interpolation = name_of(&names::INTERPOLATION_TYPES, &track.interpolation_type);
+        self.flag(interpolation.unwrap_or_default());
+        if track.global_sequence_id != u32::MAX {
+            self.field("GlobalSeqId", &track.global_sequence_id);
+        }
+        for key in &track.tracks {
+            match key {
+                Track::Linear { frame, value } => {
+                    self.line(&format!("{}: {},", frame, value.mdl()));
+                }
+                Track::Complex {
+                    frame,
+                    value,
+                    in_tan,
+                    out_tan,
+                } => {
+                    self.line(&format!("{}: {},", frame, value.mdl()));
+                    self.indent += 1;
+                    self.field("InTan", in_tan);
+                    self.field("OutTan", out_tan);
+                    self.indent -= 1;
+                }
+            }
+        }
+        self.close();
+    }
+
+    fn extent(&mut self, extent: &Extent) {
+        self.field("MinimumExtent", &extent.minimum);
+        self.field("MaximumExtent", &extent.maximum);
+        self.field("BoundsRadius", &extent.bounds_radius);
+    }
+
+    fn model(&mut self, root: &Mdlx) {
+        if let Some(vers) = &root.vers {
+            self.open("Version");
+            self.field("FormatVersion", &vers.version);
+            self.close();
+        }
+        if let Some(modl) = &root.modl {
+            self.open(&format!("Model {}", quoted(modl.name.text())));
+            self.field("BlendTime", &modl.blend_time);
+            self.extent(&modl.extent);
+            let animation_file = modl.animation_filename.text();
+            if !animation_file.is_empty() {
+                self.line(&format!("AnimationFile {},", quoted(animation_file)));
+            }
+            self.close();
+        }
+        if let Some(seqs) = &root.seqs {
+            self.open(&format!("Sequences {}", seqs.sequences.len()));
+            for sequence in &seqs.sequences {
+                self.open(&format!("Anim {}", quoted(sequence.name.text())));
+                self.field("Interval", &sequence.interval);
+                if sequence.flags == 1 {
+                    self.flag("NonLooping");
+                }
+                if sequence.move_speed != 0.0 {
+                    self.field("MoveSpeed", &sequence.move_speed);
+                }
+                if sequence.rarity != 0.0 {
+                    self.field("Rarity", &sequence.rarity);
+                }
+                if sequence.sync_point != 0 {
+                    self.field("SyncPoint", &sequence.sync_point);
+                }
+                self.extent(&sequence.extent);
+                self.close();
+            }
+            self.close();
+        }
+        if let Some(glbs) = &root.glbs {
+            self.open(&format!("GlobalSequences {}", glbs.global_sequences.len()));
+            for duration in &glbs.global_sequences {
+                self.field("Duration", duration);
+            }
+            self.close();
+        }
+        if let Some(texs) = &root.texs {
+            self.open(&format!("Textures {}", texs.textures.len()));
+            for texture in &texs.textures {
+                self.open("Bitmap");
+                self.line(&format!("Image {},", quoted(texture.file_name.text())));
+                if texture.replaceable_id != 0 {
+                    self.field("ReplaceableId", &texture.replaceable_id);
+                }
+                for (name, flag) in names::TEXTURE_FLAGS {
+                    if texture.flags & flag != 0 {
+                        self.flag(name);
+                    }
+                }
+                self.close();
+            }
+            self.close();
+        }
+        if let Some(snds) = &root.snds {
+            self.open(&format!("SoundTracks {}", snds.sound_tracks.len()));
+            for sound in &snds.sound_tracks {
+                self.open(&format!("SoundTrack {}", quoted(sound.file_name.text())));
+                self.field("Volume", &sound.volume);
+                self.field("Pitch", &sound.pitch);
+                self.field("Flags", &sound.flags);
+                self.close();
+            }
+            self.close();
+        }
+        if let Some(mtls) = &root.mtls {
+            self.open(&format!("Materials {}", mtls.materials.len()));
+            for material in &mtls.materials {
+                self.material(material);
+            }
+            self.close();
+        }
+        if let Some(txan) = &root.txan {
+            self.open(&format!("TextureAnims {}", txan.animations.len()));
+            for animation in &txan.animations {
+                self.open("TVertexAnim");
+                if let Some(t) = &animation.ktat {
+                    self.track("Translation", &t.0);
+                }
+                if let Some(t) = &animation.ktar {
+                    self.track("Rotation", &t.0);
+                }
+                if let Some(t) = &animation.ktas {
+                    self.track("Scaling", &t.0);
+                }
+                self.close();
+            }
+            self.close();
+        }
+        for geoset in root.geos.iter().flat_map(|c| &c.geosets) {
+            self.geoset(geoset);
+        }
+        for animation in root.geoa.iter().flat_map(|c| &c.animations) {
+            self.open("GeosetAnim");
+            self.line(&format!(
+                "GeosetId {},",
+                optional_id(animation.geoset_id, "None")
+            ));
+            self.animated(
+                "Alpha",
+                &animation.alpha,
+                animation.kgao.as_ref().map(|t| &t.0),
+            );
+            // Color is stored in BGR order as in the binary format
+            if animation.flags & GEOSET_ANIMATION_USE_COLOR != 0 || animation.kgac.is_some() {
+                self.animated(
+                    "Color",
+                    &animation.color,
+                    animation.kgac.as_ref().map(|t| &t.0),
+                );
+            }
+            if animation.flags & GEOSET_ANIMATION_DROP_SHADOW != 0 {
+                self.flag("DropShadow");
+            }
+            self.close();
+        }
+        for bone in root.bone.iter().flat_map(|c| &c.bones) {
+            self.node("Bone", &bone.node, &[], |w| {
+                w.line(&format!(
+                    "GeosetId {},",
+                    optional_id(bone.geoset_id, "Multiple")
+                ));
+                w.line(&format!(
+                    "GeosetAnimId {},",
+                    optional_id(bone.geoset_animation_id, "None")
+                ));
+            });
+        }
+        for light in root.lite.iter().flat_map(|c| &c.lights) {
+            self.light(light);
+        }
+        for helper in root.help.iter().flat_map(|c| &c.helpers) {
+            self.node("Helper", helper, &[], |_| {});
+        }
+        for attachment in root.atch.iter().flat_map(|c| &c.attachments) {
+            self.node("Attachment", &attachment.node, &[], |w| {
+                let path = attachment.path.text();
+                if !path.is_empty() {
+                    w.line(&format!("Path {},", quoted(path)));
+                }
+                w.field("AttachmentID", &attachment.attachment_id);
+                if let Some(t) = &attachment.katv {
+                    w.track("Visibility", &t.0);
+                }
+            });
+        }
+        if let Some(pivt) = &root.pivt {
+            self.values("PivotPoints", &pivt.points);
+        }
+        for emitter in root.prem.iter().flat_map(|c| &c.emitters) {
+            self.particle_emitter(emitter);
+        }
+        for emitter in root.pre2.iter().flat_map(|c| &c.emitters) {
+            self.particle_emitter2(emitter);
+        }
+        for emitter in root.corn.iter().flat_map(|c| &c.emitters) {
+            self.popcorn_emitter(emitter);
+        }
+        for emitter in root.ribb.iter().flat_map(|c| &c.emitters) {
+            self.ribbon_emitter(emitter);
+        }
+        for event in root.evts.iter().flat_map(|c| &c.events) {
+            self.node("EventObject", &event.node, &[], |w| {
+                w.values("EventTrack", &event.tracks);
+                if event.global_sequence_id != u32::MAX {
+                    w.field("GlobalSeqId", &event.global_sequence_id);
+                }
+            });
+        }
+        for camera in root.cams.iter().flat_map(|c| &c.cameras) {
+            self.open(&format!("Camera {}", quoted(camera.name.text())));
+            self.field("Position", &camera.position);
+            if let Some(t) = &camera.kctr {
+                self.track("Translation", &t.0);
+            }
+            if let Some(t) = &camera.kcrl {
+                self.track("Rotation", &t.0);
+            }
+            self.field("FieldOfView", &camera.field_of_view);
+            self.field("FarClip", &camera.far_clipping_plane);
+            self.field("NearClip", &camera.near_clipping_plane);
+            self.open("Target");
+            self.field("Position", &camera.target_position);
+            if let Some(t) = &camera.kttr {
+                self.track("Translation", &t.0);
+            }
+            self.close();
+            self.close();
+        }
+        for shape in root.clid.iter().flat_map(|c| &c.shapes) {
+            self.collision_shape(shape);
+        }
+        if let Some(bpos) = &root.bpos {
+            self.open("BindPose");
+            self.values("Matrices", &bpos.matrices);
+            self.close();
+        }
+        for effect in root.fafx.iter().flat_map(|c| &c.face_effects) {
+            self.open(&format!("FaceFX {}", quoted(effect.target.text())));
+            self.line(&format!("Path {},", quoted(effect.path.text())));
+            self.close();
+        }
+        for (tag, _) in &root.unknown_chunks {
+            warn!("Chunk {} has no text representation, skipping it", tag);
+        }
+    }
+
+    fn material(&mut self, material: &Material) {
+        self.open("Material");
+        for (name, flag) in names::MATERIAL_FLAGS {
+            if material.flags & flag != 0 {
+                self.flag(name);
+            }
+        }
+        if material.priority_plane != 0 {
+            self.field("PriorityPlane", &material.priority_plane);
+        }
+        if let Some(shader) = &material.shader {
+            self.line(&format!("Shader {},", quoted(shader.text())));
+        }
+        for layer in &material.layers {
+            self.layer(layer);
+        }
+        self.close();
+    }
+
+    fn layer(&mut self, layer: &Layer) {
+        self.open("Layer");
+        let filter_mode = name_of(&names::LAYER_FILTER_MODES, &layer.filter_mode);
+        self.line(&format!("FilterMode {},", filter_mode.unwrap_or_default()));
+        for (name, flag) in names::SHADING_FLAGS {
+            if layer.shading_flags.contains(flag) {
+                self.flag(name);
+            }
+        }
+        self.animated(
+            "TextureID",
+            &layer.texture_id,
+            layer.kmtf.as_ref().map(|t| &t.0),
+        );
+        if layer.texture_animation_id != u32::MAX {
+            self.field("TVertexAnimId", &layer.texture_animation_id);
+        }
+        if layer.coord_id != 0 {
+            self.field("CoordId", &layer.coord_id);
+        }
+        self.animated("Alpha", &layer.alpha, layer.kmta.as_ref().map(|t| &t.0));
+        if let Some(extra) = &layer.extra {
+            self.field("static EmissiveGain", &extra.emissive_gain);
+            self.field("static FresnelColor", &extra.fresnel_color);
+            self.field("static FresnelOpacity", &extra.fresnel_opacity);
+            self.field("static FresnelTeamColor", &extra.fresnel_team_color);
+        }
+        if let Some(t) = &layer.kmte {
+            self.track("EmissiveGain", &t.0);
+        }
+        if let Some(t) = &layer.kfc3 {
+            self.track("FresnelColor", &t.0);
+        }
+        if let Some(t) = &layer.kfca {
+            self.track("FresnelOpacity", &t.0);
+        }
+        if let Some(t) = &layer.kftc {
+            self.track("FresnelTeamColor", &t.0);
+        }
+        self.close();
+    }
+
+    fn geoset(&mut self, geoset: &Geoset) {
+        self.open("Geoset");
+        self.values("Vertices", &geoset.vertex_positions);
+        self.values("Normals", &geoset.vertex_normals);
+        for set in &geoset.texture_coordinate_sets {
+            self.values("TVertices", &set.texture_coordinates);
+        }
+        self.open("VertexGroup");
+        for group in &geoset.vertex_groups {
+            self.line(&format!("{},", group));
+        }
+        self.close();
+        if let Some(tangents) = &geoset.tangents {
+            self.values("Tangents", &tangents.tangents);
+        }
+        if let Some(skin) = &geoset.skin {
+            // Four bone indices and four weights per vertex
+            self.open(&format!("SkinWeights {}", skin.skin.len() / 8));
+            for weights in skin.skin.chunks(8) {
+                let items: Vec<String> = weights.iter().map(|v| v.mdl()).collect();
+                self.line(&format!("{},", items.join(", ")));
+            }
+            self.close();
+        }
+        self.open(&format!(
+            "Faces {} {}",
+            geoset.face_type_groups.len(),
+            geoset.faces.len()
+        ));
+        let mut faces = geoset.faces.as_slice();
+        for (face_type, count) in geoset.face_type_groups.iter().zip(&geoset.face_groups) {
+            let (group, rest) = faces.split_at((*count as usize).min(faces.len()));
+            faces = rest;
+            self.open(name_of(&names::FACE_TYPE_GROUPS, face_type).unwrap_or_default());
+            // Triangles go one per line to keep diffs readable
+            let size = if *face_type == FaceTypeGroup::Triangles {
+                3
+            } else {
+                group.len().max(1)
+            };
+            for face in group.chunks(size) {
+                let items: Vec<String> = face.iter().map(|v| v.mdl()).collect();
+                self.line(&format!("{{ {} }},", items.join(", ")));
+            }
+            self.close();
+        }
+        self.close();
+        self.open(&format!(
+            "Groups {} {}",
+            geoset.matrix_groups.len(),
+            geoset.matrix_indicies.len()
+        ));
+        let mut indices = geoset.matrix_indicies.as_slice();
+        for count in &geoset.matrix_groups {
+            let (group, rest) = indices.split_at((*count as usize).min(indices.len()));
+            indices = rest;
+            let items: Vec<String> = group.iter().map(|v| v.mdl()).collect();
+            self.line(&format!("Matrices {{ {} }},", items.join(", ")));
+        }
+        self.close();
+        self.extent(&geoset.extent);
+        for extent in &geoset.sequence_extents {
+            self.open("Anim");
+            self.extent(extent);
+            self.close();
+        }
+        self.field("MaterialID", &geoset.material_id);
+        self.field("SelectionGroup", &geoset.selection_group);
+        if geoset.selection_flags & 4 != 0 {
+            self.flag("Unselectable");
+        }
+        if let Some(lod) = &geoset.lod_extra {
+            self.field("LevelOfDetail", &lod.lod);
+            self.line(&format!("Name {},", quoted(lod.lod_name.text())));
+        }
+        self.close();
+    }
+
+    /// Write node block, fields of specific object type are written by `body`
+    fn node<F>(&mut self, kind: &str, node: &Node, flags: &[(&str, NodeFlags)], body: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.open(&format!("{} {}", kind, quoted(node.name.text())));
+        self.field("ObjectId", &node.object_id);
+        if node.parent_id != u32::MAX {
+            self.field("Parent", &node.parent_id);
+        }
+        for (name, flag) in names::NODE_FLAGS.iter().chain(flags) {
+            if node.flags.contains(*flag) {
+                self.flag(name);
+            }
+        }
+        let dont_inherit: Vec<&str> = names::DONT_INHERIT_FLAGS
+            .iter()
+            .filter(|(_, flag)| node.flags.contains(*flag))
+            .map(|(name, _)| *name)
+            .collect();
+        if !dont_inherit.is_empty() {
+            self.line(&format!("DontInherit {{ {} }},", dont_inherit.join(", ")));
+        }
+        body(self);
+        if let Some(t) = &node.kgtr {
+            self.track("Translation", &t.0);
+        }
+        if let Some(t) = &node.kgrt {
+            self.track("Rotation", &t.0);
+        }
+        if let Some(t) = &node.kgsc {
+            self.track("Scaling", &t.0);
+        }
+        self.close();
+    }
+
+    fn light(&mut self, light: &Light) {
+        self.node("Light", &light.node, &[], |w| {
+            w.flag(name_of(&names::LIGHT_TYPES, &light.light_type).unwrap_or_default());
+            w.animated(
+                "AttenuationStart",
+                &light.attenuation_start,
+                light.klas.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "AttenuationEnd",
+                &light.attenuation_end,
+                light.klae.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Intensity",
+                &light.intensity,
+                light.klai.as_ref().map(|t| &t.0),
+            );
+            w.animated("Color", &light.color, light.klac.as_ref().map(|t| &t.0));
+            w.animated(
+                "AmbIntensity",
+                &light.ambient_intensity,
+                light.klbi.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "AmbColor",
+                &light.ambient_color,
+                light.klbc.as_ref().map(|t| &t.0),
+            );
+            if let Some(t) = &light.klav {
+                w.track("Visibility", &t.0);
+            }
+        });
+    }
+
+    fn particle_emitter(&mut self, emitter: &ParticleEmitter) {
+        let flags = &names::PARTICLE_EMITTER_FLAGS;
+        self.node("ParticleEmitter", &emitter.node, flags, |w| {
+            w.animated(
+                "EmissionRate",
+                &emitter.emission_rate,
+                emitter.kpee.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Gravity",
+                &emitter.gravity,
+                emitter.kpeg.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Longitude",
+                &emitter.longitude,
+                emitter.kpln.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Latitude",
+                &emitter.latitude,
+                emitter.kplt.as_ref().map(|t| &t.0),
+            );
+            if let Some(t) = &emitter.kpev {
+                w.track("Visibility", &t.0);
+            }
+            w.open("Particle");
+            w.animated(
+                "LifeSpan",
+                &emitter.lifespan,
+                emitter.kpel.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "InitVelocity",
+                &emitter.initial_velocity,
+                emitter.kpes.as_ref().map(|t| &t.0),
+            );
+            w.line(&format!(
+                "Path {},",
+                quoted(emitter.spawn_model_file_name.text())
+            ));
+            w.close();
+        });
+    }
+
+    fn particle_emitter2(&mut self, emitter: &ParticleEmitter2) {
+        let flags = &names::PARTICLE_EMITTER2_FLAGS;
+        self.node("ParticleEmitter2", &emitter.node, flags, |w| {
+            w.animated("Speed", &emitter.speed, emitter.kp2s.as_ref().map(|t| &t.0));
+            w.animated(
+                "Variation",
+                &emitter.variation,
+                emitter.kp2r.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Latitude",
+                &emitter.latitude,
+                emitter.kp2l.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "Gravity",
+                &emitter.gravity,
+                emitter.kp2g.as_ref().map(|t| &t.0),
+            );
+            if let Some(t) = &emitter.kp2v {
+                w.track("Visibility", &t.0);
+            }
+            if emitter.squirt != 0 {
+                w.flag("Squirt");
+            }
+            w.field("LifeSpan", &emitter.lifespan);
+            w.animated(
+                "EmissionRate",
+                &emitter.emission_rate,
+                emitter.kp2e.as_ref().map(|t| &t.0),
+            );
+            w.animated("Width", &emitter.width, emitter.kp2w.as_ref().map(|t| &t.0));
+            w.animated(
+                "Length",
+                &emitter.length,
+                emitter.kp2n.as_ref().map(|t| &t.0),
+            );
+            let filter_mode = name_of(&names::EMITTER_FILTER_MODES, &emitter.filter_mode);
+            w.flag(filter_mode.unwrap_or_default());
+            w.field("Rows", &emitter.rows);
+            w.field("Columns", &emitter.columns);
+            w.flag(name_of(&names::HEAD_TAIL, &emitter.head_or_tail).unwrap_or_default());
+            w.field("TailLength", &emitter.tail_length);
+            w.field("Time", &emitter.time);
+            w.open("SegmentColor");
+            for color in &emitter.segment_color {
+                w.field("Color", color);
+            }
+            w.close();
+            w.field("Alpha", &emitter.segment_alpha);
+            w.field("ParticleScaling", &emitter.segment_scaling);
+            w.field("LifeSpanUVAnim", &emitter.head_interval);
+            w.field("DecayUVAnim", &emitter.head_decay_interval);
+            w.field("TailUVAnim", &emitter.tail_interval);
+            w.field("TailDecayUVAnim", &emitter.tail_decay_interval);
+            w.field("TextureID", &emitter.texture_id);
+            if emitter.replaceable_id != 0 {
+                w.field("ReplaceableId", &emitter.replaceable_id);
+            }
+            if emitter.priority_plane != 0 {
+                w.field("PriorityPlane", &emitter.priority_plane);
+            }
+        });
+    }
+
+    fn popcorn_emitter(&mut self, emitter: &CornEmitter) {
+        self.node("ParticleEmitterPopcorn", &emitter.node, &[], |w| {
+            w.animated(
+                "LifeSpan",
+                &emitter.life_span,
+                emitter.kppl.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "EmissionRate",
+                &emitter.emission_rate,
+                emitter.kppe.as_ref().map(|t| &t.0),
+            );
+            w.animated("Speed", &emitter.speed, emitter.kpps.as_ref().map(|t| &t.0));
+            let [r, g, b, alpha] = emitter.color;
+            w.animated("Color", &[r, g, b], emitter.kppc.as_ref().map(|t| &t.0));
+            w.animated("Alpha", &alpha, emitter.kppa.as_ref().map(|t| &t.0));
+            if let Some(t) = &emitter.kppv {
+                w.track("Visibility", &t.0);
+            }
+            if emitter.replaceable_id != 0 {
+                w.field("ReplaceableId", &emitter.replaceable_id);
+            }
+            w.line(&format!("Path {},", quoted(emitter.path.text())));
+            w.line(&format!(
+                "AnimVisibilityGuide {},",
+                quoted(emitter.flags.text())
+            ));
+        });
+    }
+
+    fn ribbon_emitter(&mut self, emitter: &RibbonEmitter) {
+        self.node("RibbonEmitter", &emitter.node, &[], |w| {
+            w.animated(
+                "HeightAbove",
+                &emitter.height_above,
+                emitter.krha.as_ref().map(|t| &t.0),
+            );
+            w.animated(
+                "HeightBelow",
+                &emitter.height_below,
+                emitter.krhb.as_ref().map(|t| &t.0),
+            );
+            w.animated("Alpha", &emitter.alpha, emitter.kral.as_ref().map(|t| &t.0));
+            w.animated("Color", &emitter.color, emitter.krco.as_ref().map(|t| &t.0));
+            w.animated(
+                "TextureSlot",
+                &emitter.texture_slot,
+                emitter.krtx.as_ref().map(|t| &t.0),
+            );
+            if let Some(t) = &emitter.krvs {
+                w.track("Visibility", &t.0);
+            }
+            w.field("EmissionRate", &emitter.emission_rate);
+            w.field("LifeSpan", &emitter.lifespan);
+            w.field("Gravity", &emitter.gravity);
+            w.field("Rows", &emitter.rows);
+            w.field("Columns", &emitter.columns);
+            w.field("MaterialID", &emitter.material_id);
+        });
+    }
+
+    fn collision_shape(&mut self, shape: &CollisionShape) {
+        let (node, kind, vertices, radius) = match shape {
+            CollisionShape::Cube { node, vertices } => (node, "Box", vertices.to_vec(), None),
+            CollisionShape::Plane { node, vertices } => (node, "Plane", vertices.to_vec(), None),
+            CollisionShape::Sphere {
+                node,
+                vertices,
+                radius,
+            } => (node, "Sphere", vec![*vertices], Some(radius)),
+            CollisionShape::Cylinder {
+                node,
+                vertices,
+                radius,
+            } => (node, "Cylinder", vertices.to_vec(), Some(radius)),
+        };
+        self.node("CollisionShape", node, &[], |w| {
+            w.flag(kind);
+            w.values("Vertices", &vertices);
+            if let Some(radius) = radius {
+                w.field("BoundsRadius", radius);
+            }
+        });
+    }
+}
@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+/// Errors that occur while reading OBJ text
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Error {
+    #[error("Line {line}: expected {expected}")]
+    Expected { line: usize, expected: &'static str },
+    #[error("Line {line}: cannot parse number {value:?}")]
+    InvalidNumber { line: usize, value: String },
+    #[error("Line {line}: index {index} is out of range")]
+    InvalidIndex { line: usize, index: i64 },
+    #[error("Geoset can hold at most 65536 vertices, found {0}")]
+    TooManyVertices(usize),
+    #[error("Model has no node with object id {0}")]
+    UnknownNode(u32),
+}
@@ -0,0 +1,9 @@
+//! Wavefront OBJ format with MTL material library. Only geometry is kept, so
+//! the format is handy for tools without skeletal support.
+pub mod error;
+mod parser;
+mod writer;
+
+pub use error::Error;
+pub use parser::append_obj;
+pub use writer::{write_obj, Obj, ObjOptions};
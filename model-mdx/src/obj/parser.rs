@@ -0,0 +1,189 @@
+use super::error::Error;
+use crate::types::chunk::utils::Literal;
+use crate::types::chunk::*;
+use crate::types::extent::Extent;
+use crate::types::geoset::*;
+use log::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Read OBJ text and append its faces to the model as a new geoset. All
+/// vertices are bound rigidly to the node `object_id` through a single
+/// matrix group. Objects and groups of the file are merged, materials are
+/// ignored in favor of `material_id`. Returns index of the new geoset.
+pub fn append_obj(
+    root: &mut Mdlx,
+    input: &str,
+    object_id: u32,
+    material_id: u32,
+) -> Result<usize, Error> {
+    if !root.nodes().any(|n| n.object_id == object_id) {
+        return Err(Error::UnknownNode(object_id));
+    }
+    let mut geoset = parse_obj(input)?;
+    geoset.vertex_groups = vec![0; geoset.vertex_positions.len()];
+    geoset.matrix_groups = vec![1];
+    geoset.matrix_indicies = vec![object_id];
+    geoset.material_id = material_id;
+    if root.vers.as_ref().map_or(800, |v| v.version) > 800 {
+        geoset.lod_extra = Some(LodExtra {
+            lod: 0,
+            lod_name: Literal {
+                content: String::new(),
+            },
+        });
+    }
+    let sequence_count = root.seqs.as_ref().map_or(0, |c| c.sequences.len());
+    geoset.sequence_extents = vec![geoset.extent.clone(); sequence_count];
+    let geosets = &mut root.geos.get_or_insert(Geos { geosets: vec![] }).geosets;
+    geosets.push(geoset);
+    Ok(geosets.len() - 1)
+}
+
+fn number<T: FromStr>(line: usize, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::InvalidNumber {
+        line,
+        value: value.to_owned(),
+    })
+}
+
+/// Parse fixed count of numbers, the rest of components are optional in OBJ
+fn vector<const N: usize>(line: usize, values: &[&str]) -> Result<[f32; N], Error> {
+    if values.len() < N {
+        return Err(Error::Expected {
+            line,
+            expected: "more components",
+        });
+    }
+    let mut result = [0.0; N];
+    for (r, v) in result.iter_mut().zip(values) {
+        *r = number(line, v)?;
+    }
+    Ok(result)
+}
+
+/// Convert index of OBJ that starts from one or counts from the end if it
+/// is negative.
+fn resolve(line: usize, index: &str, count: usize) -> Result<usize, Error> {
+    let index: i64 = number(line, index)?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(Error::InvalidIndex { line, index });
+    }
+    Ok(resolved as usize)
+}
+
+/// Geoset with geometry of the OBJ file. Every distinct combination of
+/// position, texture coordinate and normal becomes a vertex, polygons are
+/// split into triangle fans.
+fn parse_obj(input: &str) -> Result<Geoset, Error> {
+    let mut positions: Vec<[f32; 3]> = vec![];
+    let mut coords: Vec<[f32; 2]> = vec![];
+    let mut normals: Vec<[f32; 3]> = vec![];
+    let mut vertices: HashMap<(usize, Option<usize>, Option<usize>), u16> = HashMap::new();
+    let mut geoset = Geoset {
+        vertex_positions: vec![],
+        vertex_normals: vec![],
+        face_type_groups: vec![FaceTypeGroup::Triangles],
+        face_groups: vec![],
+        faces: vec![],
+        vertex_groups: vec![],
+        matrix_groups: vec![],
+        matrix_indicies: vec![],
+        material_id: 0,
+        selection_group: 0,
+        selection_flags: 0,
+        lod_extra: None,
+        extent: Extent::from_points(&[]),
+        sequence_extents: vec![],
+        tangents: None,
+        skin: None,
+        ordered: None,
+        texture_coordinate_sets: vec![],
+    };
+    let mut vertex_coords = vec![];
+    let mut vertex_normals = vec![];
+    let mut has_normals = true;
+
+    for (i, content) in input.lines().enumerate() {
+        let line = i + 1;
+        let content = content.split('#').next().unwrap_or_default();
+        let mut words = content.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+        let values: Vec<&str> = words.collect();
+        match keyword {
+            "v" => positions.push(vector(line, &values)?),
+            // Texture space of OBJ starts at the bottom
+            "vt" => {
+                let [u, v] = vector(line, &values)?;
+                coords.push([u, 1.0 - v]);
+            }
+            "vn" => normals.push(vector(line, &values)?),
+            "f" => {
+                if values.len() < 3 {
+                    return Err(Error::Expected {
+                        line,
+                        expected: "at least three vertices of face",
+                    });
+                }
+                let mut polygon = vec![];
+                for value in values {
+                    let mut parts = value.split('/');
+                    let position =
+                        resolve(line, parts.next().unwrap_or_default(), positions.len())?;
+                    let coord = match parts.next().filter(|p| !p.is_empty()) {
+                        Some(p) => Some(resolve(line, p, coords.len())?),
+                        None => None,
+                    };
+                    let normal = match parts.next().filter(|p| !p.is_empty()) {
+                        Some(p) => Some(resolve(line, p, normals.len())?),
+                        None => None,
+                    };
+                    let key = (position, coord, normal);
+                    let index = match vertices.get(&key) {
+                        Some(index) => *index,
+                        None => {
+                            let count = geoset.vertex_positions.len();
+                            if count > usize::from(u16::MAX) {
+                                return Err(Error::TooManyVertices(count + 1));
+                            }
+                            geoset.vertex_positions.push(positions[position]);
+                            vertex_coords.push(coord.map_or([0.0; 2], |c| coords[c]));
+                            has_normals &= normal.is_some();
+                            vertex_normals.push(normal.map_or([0.0; 3], |n| normals[n]));
+                            vertices.insert(key, count as u16);
+                            count as u16
+                        }
+                    };
+                    polygon.push(index);
+                }
+                for pair in polygon[1..].windows(2) {
+                    geoset.faces.extend([polygon[0], pair[0], pair[1]]);
+                }
+            }
+            "o" | "g" | "s" | "usemtl" | "mtllib" | "l" | "p" => (),
+            _ => trace!(
+                "Line {}: skipping unsupported statement {:?}",
+                line,
+                keyword
+            ),
+        }
+    }
+    geoset.face_groups = vec![geoset.faces.len() as u32];
+    geoset.texture_coordinate_sets = vec![TextureCoordinateSet {
+        texture_coordinates: vertex_coords,
+    }];
+    geoset.vertex_normals = if has_normals {
+        vertex_normals
+    } else {
+        geoset.smooth_normals()
+    };
+    geoset.extent = Extent::from_points(&geoset.vertex_positions);
+    Ok(geoset)
+}
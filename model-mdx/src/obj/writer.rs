@@ -0,0 +1,139 @@
+use crate::pose::{Pose, SkinnedGeoset};
+use crate::types::chunk::Mdlx;
+use crate::types::MdxModel;
+use std::fmt::Write;
+
+/// Options of OBJ export
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjOptions {
+    /// Name of MTL file that is referred by `mtllib` statement
+    pub material_library: String,
+    /// Index of sequence to pose the mesh at. Without it geosets are written
+    /// as they are stored.
+    pub sequence: Option<usize>,
+    /// Frame of the model timeline, it is clamped into the sequence interval
+    pub frame: u32,
+}
+
+/// Texts of OBJ file and of its MTL material library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Obj {
+    pub obj: String,
+    pub mtl: String,
+}
+
+/// Write all geosets as triangulated objects with normals and the first set
+/// of texture coordinates. Coordinates are written as they are, Z axis
+/// points up. Each material becomes `Material{id}` that refers the texture
+/// of its first layer.
+pub fn write_obj(model: &MdxModel, options: &ObjOptions) -> Obj {
+    let root = &model.root;
+    Obj {
+        obj: write_geosets(root, options),
+        mtl: write_materials(root),
+    }
+}
+
+fn write_geosets(root: &Mdlx, options: &ObjOptions) -> String {
+    let mut out = String::new();
+    if !options.material_library.is_empty() {
+        let _ = writeln!(out, "mtllib {}", options.material_library);
+    }
+    let pose = options
+        .sequence
+        .map(|sequence| Pose::new(root, sequence, options.frame));
+    let material_count = root.mtls.as_ref().map_or(0, |c| c.materials.len());
+    // Indices of OBJ are global and start from one, each kind of vertex
+    // data is counted separately
+    let mut position_offset = 1;
+    let mut coord_offset = 1;
+    let mut normal_offset = 1;
+    for (i, geoset) in root.geos.iter().flat_map(|c| &c.geosets).enumerate() {
+        let SkinnedGeoset { positions, normals } = match &pose {
+            Some(pose) => pose.skin(geoset),
+            None => SkinnedGeoset {
+                positions: geoset.vertex_positions.clone(),
+                normals: geoset.vertex_normals.clone(),
+            },
+        };
+        let count = positions.len();
+        let coords = geoset
+            .texture_coordinate_sets
+            .first()
+            .map(|s| s.texture_coordinates.as_slice())
+            .filter(|c| c.len() == count);
+        let has_normals = normals.len() == count;
+
+        let _ = writeln!(out, "o Geoset{}", i);
+        for [x, y, z] in &positions {
+            let _ = writeln!(out, "v {} {} {}", x, y, z);
+        }
+        // Texture space of OBJ starts at the bottom
+        for [u, v] in coords.unwrap_or_default() {
+            let _ = writeln!(out, "vt {} {}", u, 1.0 - v);
+        }
+        if has_normals {
+            for [x, y, z] in &normals {
+                let _ = writeln!(out, "vn {} {} {}", x, y, z);
+            }
+        }
+        if (geoset.material_id as usize) < material_count {
+            let _ = writeln!(out, "usemtl Material{}", geoset.material_id);
+        }
+        for triangle in geoset.triangles() {
+            if triangle.iter().any(|v| usize::from(*v) >= count) {
+                continue;
+            }
+            let vertices: Vec<String> = triangle
+                .iter()
+                .map(|v| {
+                    let v = usize::from(*v);
+                    let position = position_offset + v;
+                    let coord = coord_offset + v;
+                    let normal = normal_offset + v;
+                    match (coords.is_some(), has_normals) {
+                        (true, true) => format!("{}/{}/{}", position, coord, normal),
+                        (true, false) => format!("{}/{}", position, coord),
+                        (false, true) => format!("{}//{}", position, normal),
+                        (false, false) => format!("{}", position),
+                    }
+                })
+                .collect();
+            let _ = writeln!(out, "f {}", vertices.join(" "));
+        }
+        position_offset += count;
+        if coords.is_some() {
+            coord_offset += count;
+        }
+        if has_normals {
+            normal_offset += count;
+        }
+    }
+    out
+}
+
+fn write_materials(root: &Mdlx) -> String {
+    let mut out = String::new();
+    let textures = root.texs.as_ref().map(|c| c.textures.as_slice());
+    for (i, material) in root.mtls.iter().flat_map(|c| &c.materials).enumerate() {
+        let _ = writeln!(out, "newmtl Material{}", i);
+        let _ = writeln!(out, "Kd 1 1 1");
+        let Some(layer) = material.layers.first() else {
+            continue;
+        };
+        if layer.alpha < 1.0 {
+            let _ = writeln!(out, "d {}", layer.alpha);
+        }
+        let texture = textures.and_then(|t| t.get(layer.texture_id as usize));
+        match texture {
+            Some(texture) if !texture.file_name.text().is_empty() => {
+                let _ = writeln!(out, "map_Kd {}", texture.file_name.text());
+            }
+            Some(texture) => {
+                let _ = writeln!(out, "# Replaceable texture {}", texture.replaceable_id);
+            }
+            None => (),
+        }
+    }
+    out
+}
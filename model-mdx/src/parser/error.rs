@@ -11,20 +11,25 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("{0}")]
-    Parsing(String),
+    Parsing(LocatedError),
     #[error("Input stream is incomplete, needed: {0:?}")]
     Incomplete(nom::Needed),
+    #[error("Failed to read input: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 // Errors that occur while decoding MDX models from bytes
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum MdxParseError<I: fmt::Debug> {
     /// Parser error (not enough input and e.t.c)
     #[error("Error {1:?} at: {0:?}")]
     Nom(I, ErrorKind),
-    /// Added context for debug.
-    #[error("Context: {0}. Error: {1}")]
-    Context(String, Box<Self>),
+    /// Added context for debug. Holds input at the start of the context.
+    #[error("Context: {1}. Error: {2}")]
+    Context(I, String, Box<Self>),
+    /// Error occured in element with given index of a collection
+    #[error("Element: {0}. Error: {1}")]
+    Element(usize, Box<Self>),
     /// Raised when we expect specific type of chunk, but the tag
     /// in the file differs.
     #[error("Expected chunk {0}, but got {1}")]
@@ -84,7 +89,136 @@ impl<'a> ParseError<&'a [u8]> for MdxParseError<&'a [u8]> {
 }
 
 impl<'a> ContextError<&'a [u8]> for MdxParseError<&'a [u8]> {
-    fn add_context(_input: &'a [u8], ctx: &'static str, other: Self) -> Self {
-        MdxParseError::Context(ctx.to_owned(), Box::new(other))
+    fn add_context(input: &'a [u8], ctx: &'static str, other: Self) -> Self {
+        MdxParseError::Context(input, ctx.to_owned(), Box::new(other))
+    }
+}
+
+impl MdxParseError<&[u8]> {
+    /// Find location of the error inside `origin` input, that is the input
+    /// the whole parsing started from.
+    pub fn locate(self, origin: &[u8]) -> LocatedError {
+        self.locate_from(origin.as_ptr() as usize)
+    }
+
+    pub(crate) fn locate_from(self, base: usize) -> LocatedError {
+        let offset_of = |input: &[u8]| (input.as_ptr() as usize).saturating_sub(base);
+        let mut offset = 0;
+        let mut path = vec![];
+        let mut current = self;
+        let cause = loop {
+            current = match current {
+                MdxParseError::Context(input, name, inner) => {
+                    offset = offset_of(input);
+                    path.push(PathSegment { name, index: None });
+                    *inner
+                }
+                MdxParseError::Element(index, inner) => {
+                    match path.last_mut() {
+                        Some(segment) if segment.index.is_none() => segment.index = Some(index),
+                        _ => path.push(PathSegment {
+                            name: String::new(),
+                            index: Some(index),
+                        }),
+                    }
+                    *inner
+                }
+                MdxParseError::Nom(input, kind) => {
+                    offset = offset_of(input);
+                    break MdxParseError::Nom(offset, kind);
+                }
+                MdxParseError::UnexpectedChunkTag(expected, found) => {
+                    break MdxParseError::UnexpectedChunkTag(expected, found)
+                }
+                MdxParseError::UnknownTag(tag) => break MdxParseError::UnknownTag(tag),
+                MdxParseError::UnknownCollisionShape { tag } => {
+                    break MdxParseError::UnknownCollisionShape { tag }
+                }
+                MdxParseError::UnknownLightType(e) => break MdxParseError::UnknownLightType(e),
+                MdxParseError::TooShortLiteral { expected, found } => {
+                    break MdxParseError::TooShortLiteral { expected, found }
+                }
+                MdxParseError::Utf8Conv(e) => break MdxParseError::Utf8Conv(e),
+                MdxParseError::UnknownLayerFilterMode(e) => {
+                    break MdxParseError::UnknownLayerFilterMode(e)
+                }
+                MdxParseError::UnknownEmitterFilterMode(e) => {
+                    break MdxParseError::UnknownEmitterFilterMode(e)
+                }
+                MdxParseError::UnknownHeadTail(e) => break MdxParseError::UnknownHeadTail(e),
+                MdxParseError::UnknownInterpolationType(e) => {
+                    break MdxParseError::UnknownInterpolationType(e)
+                }
+                MdxParseError::UnknownFaceTypeGroup(e) => {
+                    break MdxParseError::UnknownFaceTypeGroup(e)
+                }
+                MdxParseError::ChunkNotEnoughInput { size, input } => {
+                    break MdxParseError::ChunkNotEnoughInput { size, input }
+                }
+                MdxParseError::ChunkLeftover { input } => {
+                    break MdxParseError::ChunkLeftover { input }
+                }
+                MdxParseError::InclusiveSizeTooSmall { size } => {
+                    break MdxParseError::InclusiveSizeTooSmall { size }
+                }
+                MdxParseError::InclusiveSizeNotEhoughInput { size, input } => {
+                    break MdxParseError::InclusiveSizeNotEhoughInput { size, input }
+                }
+                MdxParseError::InclusiveLeftover { input } => {
+                    break MdxParseError::InclusiveLeftover { input }
+                }
+            }
+        };
+        LocatedError {
+            offset,
+            path: ErrorPath(path),
+            cause,
+        }
+    }
+}
+
+/// Decoding error with location of the problem in the input. The cause
+/// holds byte offsets instead of input slices.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{cause} (at offset {offset}, path {path})")]
+pub struct LocatedError {
+    /// Absolute offset of the broken field from the start of input
+    pub offset: usize,
+    /// Chain of parser contexts that lead to the error
+    pub path: ErrorPath,
+    /// Typed reason of the failure
+    pub cause: MdxParseError<usize>,
+}
+
+/// Chain of parser contexts like `MDLX/GEOS/geosets[3]/texture_coordinate_sets[0]`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ErrorPath(pub Vec<PathSegment>);
+
+impl fmt::Display for ErrorPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Single parser context with optional index of element in collection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSegment {
+    pub name: String,
+    pub index: Option<usize>,
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(i) = self.index {
+            write!(f, "[{}]", i)?;
+        }
+        Ok(())
     }
 }
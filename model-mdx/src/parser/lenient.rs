@@ -0,0 +1,137 @@
+use super::error::{LocatedError, MdxParseError};
+use super::ParseOptions;
+use crate::types::chunk::utils::Tag;
+use std::cell::RefCell;
+use std::fmt;
+
+/// Problems that were found and skipped while parsing in lenient mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// Top level chunk failed to parse and was dropped from the model
+    SkippedChunk {
+        tag: Tag,
+        offset: usize,
+        size: usize,
+        error: LocatedError,
+    },
+    /// Top level chunk was parsed, but it has bytes that are not covered by parser
+    ChunkLeftover {
+        tag: Tag,
+        offset: usize,
+        leftover: usize,
+    },
+    /// Chunk header claims more bytes than there are left in the file, the rest
+    /// of the file is ignored.
+    TruncatedChunk {
+        tag: Tag,
+        offset: usize,
+        size: usize,
+        input: usize,
+    },
+    /// Not enough bytes for chunk header at the end of the file
+    TrailingBytes { offset: usize, size: usize },
+    /// Object with inclusive size (geoset, layer, material and e.t.c) failed to
+    /// parse and was dropped from its chunk.
+    SkippedObject {
+        offset: usize,
+        size: u32,
+        error: LocatedError,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::SkippedChunk {
+                tag,
+                offset,
+                size,
+                error,
+            } => write!(
+                f,
+                "Skipped chunk {tag} of size {size} at offset {offset}: {error}"
+            ),
+            ParseWarning::ChunkLeftover {
+                tag,
+                offset,
+                leftover,
+            } => write!(
+                f,
+                "Chunk {tag} at offset {offset} has {leftover} bytes not consumed by parser"
+            ),
+            ParseWarning::TruncatedChunk {
+                tag,
+                offset,
+                size,
+                input,
+            } => write!(
+                f,
+                "Chunk {tag} at offset {offset} has size {size}, but only {input} bytes left"
+            ),
+            ParseWarning::TrailingBytes { offset, size } => {
+                write!(f, "Ignored {size} trailing bytes at offset {offset}")
+            }
+            ParseWarning::SkippedObject {
+                offset,
+                size,
+                error,
+            } => write!(
+                f,
+                "Skipped object of size {size} at offset {offset}: {error}"
+            ),
+        }
+    }
+}
+
+/// State of a single parse that is passed explicitly to the parsers that
+/// are able to recover from errors. Parsers that get strict context fail on
+/// the first error.
+#[derive(Debug, Default)]
+pub struct ParseContext {
+    options: ParseOptions,
+    base: usize,
+    warnings: RefCell<Vec<ParseWarning>>,
+}
+
+impl ParseContext {
+    /// Context of parsing `input` with given options, offsets in warnings are
+    /// counted from the start of `input`.
+    pub fn new(options: &ParseOptions, input: &[u8]) -> Self {
+        ParseContext {
+            options: options.clone(),
+            base: input.as_ptr() as usize,
+            warnings: RefCell::new(vec![]),
+        }
+    }
+
+    /// Context that doesn't recover from errors
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if parser should recover from errors instead of failing
+    pub fn is_lenient(&self) -> bool {
+        self.options.lenient
+    }
+
+    /// Warnings that were collected while parsing
+    pub fn into_warnings(self) -> Vec<ParseWarning> {
+        self.warnings.into_inner()
+    }
+
+    /// Offset of the given input from the start of the parsed file
+    pub(crate) fn offset(&self, input: &[u8]) -> usize {
+        (input.as_ptr() as usize).saturating_sub(self.base)
+    }
+
+    /// Find location of the error relative to the start of the parsed file
+    pub(crate) fn locate(&self, err: MdxParseError<&[u8]>) -> LocatedError {
+        err.locate_from(self.base)
+    }
+
+    /// Record warning about skipped part of the model
+    pub(crate) fn warn(&self, warning: ParseWarning) {
+        log::warn!("{}", warning);
+        self.warnings.borrow_mut().push(warning);
+    }
+}
@@ -1,28 +1,71 @@
 pub mod error;
+pub mod lenient;
 pub mod primitives;
 
-use crate::types::{
-    chunk::Mdlx,
-    materialize::Materialized,
-    MdxModel,
-};
+use crate::types::{chunk::Mdlx, MdxModel};
 use error::*;
-use nom::IResult;
+pub use lenient::{ParseContext, ParseWarning};
+use nom::{error::context, IResult};
+use std::io::Read;
 
 /// Binary parser for BLP format that produces [Error] when something went wrong
 pub type Parser<'a, T> = IResult<&'a [u8], T, MdxParseError<&'a [u8]>>;
 
+/// Options that control decoding of MDX models
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Skip objects and chunks that fail to parse instead of failing the whole
+    /// model. Skipped parts are reported as [ParseWarning].
+    pub lenient: bool,
+    /// Version of format that is assumed until VERS chunk is found
+    pub version: Option<u32>,
+}
+
 /// Parse MDX model from input bytes
 pub fn parse_mdx(input: &[u8]) -> Result<MdxModel, Error> {
-    match mdx_parser(input) {
-        Ok((_, mdx)) => Ok(mdx),
-        Err(nom::Err::Incomplete(needed)) => Err(Error::Incomplete(needed)),
-        Err(nom::Err::Error(e)) => Err(Error::Parsing(format!("{}", e))),
-        Err(nom::Err::Failure(e)) => Err(Error::Parsing(format!("{}", e))),
+    let (mdx, _) = parse_mdx_with(input, &ParseOptions::default())?;
+    Ok(mdx)
+}
+
+/// Parse MDX model from input bytes with given options. Returns the model and
+/// warnings about skipped parts of the model (only in lenient mode).
+pub fn parse_mdx_with(
+    input: &[u8],
+    options: &ParseOptions,
+) -> Result<(MdxModel, Vec<ParseWarning>), Error> {
+    let parse_context = ParseContext::new(options, input);
+    let result = mdx_parser(options.version, input, &parse_context);
+    let warnings = parse_context.into_warnings();
+    match result {
+        Ok((_, mdx)) => Ok((mdx, warnings)),
+        Err(e) => Err(located(input, e)),
     }
 }
 
-fn mdx_parser(input: &[u8]) -> Parser<MdxModel> {
-    let (input, mdxl_chunk) = Mdlx::parse(input)?;
+/// Convert parser error into [Error] with offsets counted from `origin`
+pub(crate) fn located(origin: &[u8], err: nom::Err<MdxParseError<&[u8]>>) -> Error {
+    match err {
+        nom::Err::Incomplete(needed) => Error::Incomplete(needed),
+        nom::Err::Error(e) => Error::Parsing(e.locate(origin)),
+        nom::Err::Failure(e) => Error::Parsing(e.locate(origin)),
+    }
+}
+
+/// Read MDX model from the reader. Parsers work on byte slices, so input is
+/// read until the end first.
+pub fn read_mdx<R: Read>(mut reader: R) -> Result<MdxModel, Error> {
+    let mut input = vec![];
+    reader.read_to_end(&mut input)?;
+    parse_mdx(&input)
+}
+
+fn mdx_parser<'a>(
+    version: Option<u32>,
+    input: &'a [u8],
+    parse_context: &ParseContext,
+) -> Parser<'a, MdxModel> {
+    let (input, mdxl_chunk) = context("MDLX", |input| {
+        Mdlx::parse_with(version, input, parse_context)
+    })(input)?;
     Ok((input, MdxModel { root: mdxl_chunk }))
 }
@@ -1,13 +1,11 @@
+use super::error::MdxParseError;
 use super::Parser;
-use nom::{bytes::complete::take, multi::count};
+use nom::{error::ErrorKind, number::complete::le_u32};
 
 /// Consume 4 bytes and interpret them as LE float 32 bit
 pub fn le_f32(input: &[u8]) -> Parser<f32> {
-    let mut bytes: [u8; 4] = Default::default();
-    let (input, bytes_vec) = take(4usize)(input)?;
-    assert_eq!(bytes_vec.len(), 4);
-    bytes.copy_from_slice(&bytes_vec);
-    Ok((input, f32::from_le_bytes(bytes)))
+    let (input, bits) = le_u32(input)?;
+    Ok((input, f32::from_bits(bits)))
 }
 
 /// Combinator that applies N times primitive parser and returns result
@@ -20,10 +18,33 @@ where
 {
     move |input| {
         let mut res: [T; N] = [Default::default(); N];
-        let (input, res_vec) = count(parser, N)(input)?;
-        assert_eq!(res_vec.len(), N);
-        res.copy_from_slice(&res_vec);
-        Ok((input, res))
+        let mut cycle_input = input;
+        for v in res.iter_mut() {
+            let (input, value) = parser(cycle_input)?;
+            cycle_input = input;
+            *v = value;
+        }
+        Ok((cycle_input, res))
+    }
+}
+
+/// Apply parser N times and collect results. Unlike [nom::multi::count] it
+/// doesn't preallocate memory for all N elements as N usually comes from
+/// untrusted input and can be arbitrary large.
+pub fn count<'a, P, T>(mut parser: P, n: usize) -> impl FnMut(&'a [u8]) -> Parser<'a, Vec<T>>
+where
+    P: FnMut(&'a [u8]) -> Parser<T>,
+{
+    move |input| {
+        let mut cycle_input = input;
+        let max_capacity = input.len() / std::mem::size_of::<T>().max(1);
+        let mut vec = Vec::with_capacity(n.min(max_capacity));
+        for i in 0..n {
+            let (input, value) = parser(cycle_input).map_err(|e| in_element(i, e))?;
+            cycle_input = input;
+            vec.push(value);
+        }
+        Ok((cycle_input, vec))
     }
 }
 
@@ -35,11 +56,23 @@ where
     move |input| {
         let mut cycle_input = input;
         let mut vec = vec![];
-        while cycle_input.len() > 0 {
-            let (input, value) = parse(cycle_input)?;
+        while !cycle_input.is_empty() {
+            let (input, value) = parse(cycle_input).map_err(|e| in_element(vec.len(), e))?;
+            if input.len() == cycle_input.len() {
+                // Parser that doesn't consume anything would loop forever
+                return Err(nom::Err::Error(MdxParseError::Nom(
+                    cycle_input,
+                    ErrorKind::Many0,
+                )));
+            }
             cycle_input = input;
             vec.push(value);
         }
         Ok((cycle_input, vec))
     }
 }
+
+/// Mark error as occured inside element of collection with the given index
+fn in_element(index: usize, err: nom::Err<MdxParseError<&[u8]>>) -> nom::Err<MdxParseError<&[u8]>> {
+    err.map(|e| MdxParseError::Element(index, Box::new(e)))
+}
@@ -0,0 +1,118 @@
+//! Posing of the model at a frame of a sequence. Geosets are skinned on
+//! CPU, so posed geometry is available for exporters, thumbnails and
+//! collision checks.
+use crate::math::{self, Mat4};
+use crate::sampling::Time;
+use crate::scene::{Scene, Transforms};
+use crate::types::chunk::Mdlx;
+use crate::types::geoset::Geoset;
+
+/// Matrices of all nodes posed at a frame of a sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pose {
+    transforms: Transforms,
+}
+
+/// Vertices of a geoset moved by the posed nodes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkinnedGeoset {
+    pub positions: Vec<[f32; 3]>,
+    /// Empty if the geoset has no normals
+    pub normals: Vec<[f32; 3]>,
+}
+
+/// Positions and normals of the geoset of the model posed at the frame of
+/// the sequence
+pub fn skin_geoset(model: &Mdlx, geoset: &Geoset, sequence: usize, frame: u32) -> SkinnedGeoset {
+    Pose::new(model, sequence, frame).skin(geoset)
+}
+
+impl Pose {
+    /// Pose nodes at the frame of model timeline that is clamped into the
+    /// sequence interval
+    pub fn new(model: &Mdlx, sequence: usize, frame: u32) -> Self {
+        let time = Time::new(model, sequence, frame);
+        Pose {
+            transforms: Scene::new(model).transforms(&time),
+        }
+    }
+
+    /// Pose from already evaluated node transforms, e.g. the ones that
+    /// turn billboards to the camera
+    pub fn from_transforms(transforms: Transforms) -> Self {
+        Pose { transforms }
+    }
+
+    /// World matrix of the node, identity for unknown nodes
+    pub fn matrix(&self, object_id: u32) -> Mat4 {
+        self.transforms.world(object_id)
+    }
+
+    /// Positions and normals of the geoset moved by its nodes. Vertices are
+    /// weighted by HD skin if the geoset has it. Otherwise vertex group
+    /// selects matrix group and nodes of the group are averaged.
+    pub fn skin(&self, geoset: &Geoset) -> SkinnedGeoset {
+        let groups: Vec<Mat4> = geoset
+            .matrices()
+            .into_iter()
+            .map(|group| {
+                let matrices: Vec<Mat4> = group.iter().map(|id| self.matrix(*id)).collect();
+                blend(matrices.iter().map(|m| (m, 1.0)))
+            })
+            .collect();
+        let vertex_matrix = |v: usize| -> Mat4 {
+            if let Some(skin) = &geoset.skin {
+                let Some((bones, weights)) = skin.vertex(v) else {
+                    return math::IDENTITY;
+                };
+                let matrices: Vec<(Mat4, f32)> = bones
+                    .iter()
+                    .zip(weights)
+                    .filter(|(_, w)| *w > 0)
+                    .filter_map(|(b, w)| {
+                        let id = geoset.matrix_indicies.get(usize::from(*b))?;
+                        Some((self.matrix(*id), f32::from(w)))
+                    })
+                    .collect();
+                blend(matrices.iter().map(|(m, w)| (m, *w)))
+            } else {
+                geoset
+                    .vertex_groups
+                    .get(v)
+                    .and_then(|g| groups.get(usize::from(*g)))
+                    .copied()
+                    .unwrap_or(math::IDENTITY)
+            }
+        };
+        let mut positions = vec![];
+        let mut normals = vec![];
+        for (v, position) in geoset.vertex_positions.iter().enumerate() {
+            let matrix = vertex_matrix(v);
+            positions.push(math::transform_point(&matrix, *position));
+            if let Some(normal) = geoset.vertex_normals.get(v) {
+                normals.push(math::normalize(math::transform_vector(&matrix, *normal)));
+            }
+        }
+        SkinnedGeoset { positions, normals }
+    }
+}
+
+/// Weighted average of matrices, identity if there are no weights
+fn blend<'a, I>(matrices: I) -> Mat4
+where
+    I: Iterator<Item = (&'a Mat4, f32)>,
+{
+    let mut result = [0.0; 16];
+    let mut total = 0.0;
+    for (matrix, weight) in matrices {
+        for (r, m) in result.iter_mut().zip(matrix) {
+            *r += m * weight;
+        }
+        total += weight;
+    }
+    if total > 0.0 {
+        result.map(|r| r / total)
+    } else {
+        math::IDENTITY
+    }
+}
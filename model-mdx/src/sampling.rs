@@ -0,0 +1,217 @@
+//! Evaluation of keyframe tracks at a moment of the model timeline. Tracks
+//! with global sequence run over their own loop, the rest are bound to the
+//! interval of the playing sequence.
+use crate::math::{self, Quat};
+use crate::types::chunk::Mdlx;
+use crate::types::tracks::*;
+
+/// Values of tracks that can be interpolated between keys
+pub trait Interpolate: Clone {
+    /// Value between `self` at `t = 0` and `other` at `t = 1`
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+
+    /// Cubic Hermite spline between `self` and `other`, `out_tan` is the
+    /// outgoing tangent of `self` and `in_tan` is the incoming tangent of
+    /// `other`.
+    fn hermite(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self;
+
+    /// Cubic Bezier curve between `self` and `other` with control points
+    /// `out_tan` and `in_tan`
+    fn bezier(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self;
+
+    /// Outgoing and incoming Hermite tangents that turn the spline between
+    /// `self` and `other` into a straight segment
+    fn secants(&self, other: &Self) -> (Self, Self);
+}
+
+impl Interpolate for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    fn hermite(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        let t2 = t * t;
+        let f1 = t2 * (2.0 * t - 3.0) + 1.0;
+        let f2 = t2 * (t - 2.0) + t;
+        let f3 = t2 * (t - 1.0);
+        let f4 = t2 * (3.0 - 2.0 * t);
+        self * f1 + out_tan * f2 + in_tan * f3 + other * f4
+    }
+
+    fn bezier(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        let inv = 1.0 - t;
+        let f1 = inv * inv * inv;
+        let f2 = 3.0 * t * inv * inv;
+        let f3 = 3.0 * t * t * inv;
+        let f4 = t * t * t;
+        self * f1 + out_tan * f2 + in_tan * f3 + other * f4
+    }
+
+    fn secants(&self, other: &Self) -> (Self, Self) {
+        (other - self, other - self)
+    }
+}
+
+impl Interpolate for [f32; 3] {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(&other[i], t))
+    }
+
+    fn hermite(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].hermite(&out_tan[i], &in_tan[i], &other[i], t))
+    }
+
+    fn bezier(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].bezier(&out_tan[i], &in_tan[i], &other[i], t))
+    }
+
+    fn secants(&self, other: &Self) -> (Self, Self) {
+        let secant = std::array::from_fn(|i| other[i] - self[i]);
+        (secant, secant)
+    }
+}
+
+/// Tracks of four components are quaternions, both spline kinds become
+/// squad with tangents as control quaternions
+impl Interpolate for Quat {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        math::quat_slerp(*self, *other, t)
+    }
+
+    fn hermite(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        math::quat_squad(*self, *out_tan, *in_tan, *other, t)
+    }
+
+    fn bezier(&self, out_tan: &Self, in_tan: &Self, other: &Self, t: f32) -> Self {
+        math::quat_squad(*self, *out_tan, *in_tan, *other, t)
+    }
+
+    fn secants(&self, other: &Self) -> (Self, Self) {
+        (*self, *other)
+    }
+}
+
+/// Integer tracks like texture ids switch at keys
+impl Interpolate for u32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        if t < 1.0 {
+            *self
+        } else {
+            *other
+        }
+    }
+
+    fn hermite(&self, _: &Self, _: &Self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+
+    fn bezier(&self, _: &Self, _: &Self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+
+    fn secants(&self, other: &Self) -> (Self, Self) {
+        (*self, *other)
+    }
+}
+
+/// Moment of the model timeline to sample tracks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Time {
+    /// Interval of the playing sequence
+    pub interval: [u32; 2],
+    /// Frame of the model timeline inside of the interval
+    pub frame: u32,
+    /// Frames since the model started playing, global sequences loop over it
+    pub global_frame: u32,
+    /// Durations of global sequences, usually taken from `GLBS` chunk
+    pub global_sequences: Vec<u32>,
+}
+
+impl Time {
+    /// Frame of the model timeline that is clamped into interval of the
+    /// sequence. Global sequences run for the same time since the sequence
+    /// start.
+    pub fn new(model: &Mdlx, sequence: usize, frame: u32) -> Self {
+        let interval = model
+            .seqs
+            .as_ref()
+            .and_then(|c| c.sequences.get(sequence))
+            .map_or([0, 0], |s| s.interval);
+        let frame = frame.min(interval[1]).max(interval[0]);
+        Time {
+            interval,
+            frame,
+            global_frame: frame - interval[0],
+            global_sequences: model
+                .glbs
+                .as_ref()
+                .map_or(vec![], |c| c.global_sequences.clone()),
+        }
+    }
+
+    /// Interval and frame that the track is bound to
+    fn resolve(&self, global_sequence_id: u32) -> ([u32; 2], u32) {
+        match self.global_sequences.get(global_sequence_id as usize) {
+            Some(0) => ([0, 0], 0),
+            Some(duration) => ([0, *duration], self.global_frame % duration),
+            None => (self.interval, self.frame),
+        }
+    }
+}
+
+impl<T: Interpolate> TrackChunk<T> {
+    /// Value of the track at the moment. Only keys inside of the interval
+    /// are used, the first and the last keys hold their values up to the
+    /// interval bounds. Returns `None` if the interval has no keys, then
+    /// static value of the owner applies.
+    pub fn sample(&self, time: &Time) -> Option<T> {
+        let ([start, end], frame) = time.resolve(self.global_sequence_id);
+        let [start, end, frame] = [start, end, frame].map(i64::from);
+        let keys: Vec<&Track<T>> = self
+            .tracks
+            .iter()
+            .filter(|k| (start..=end).contains(&i64::from(k.frame())))
+            .collect();
+        let next = keys.iter().position(|k| i64::from(k.frame()) > frame);
+        let (prev, next) = match next {
+            Some(0) => return Some(keys[0].value().clone()),
+            Some(i) => (keys[i - 1], keys[i]),
+            None => return keys.last().map(|k| k.value().clone()),
+        };
+        let span = (next.frame() - prev.frame()) as f32;
+        let t = (frame - i64::from(prev.frame())) as f32 / span;
+        let (a, b) = (prev.value(), next.value());
+        let value = match self.interpolation_type {
+            InterpolationType::None => a.clone(),
+            InterpolationType::Linear => a.lerp(b, t),
+            InterpolationType::Hermite => {
+                let (out_secant, in_secant) = a.secants(b);
+                let out_tan = out_tan(prev).unwrap_or(&out_secant);
+                let in_tan = in_tan(next).unwrap_or(&in_secant);
+                a.hermite(out_tan, in_tan, b, t)
+            }
+            InterpolationType::Bezier => {
+                let out_tan = out_tan(prev).unwrap_or(a);
+                let in_tan = in_tan(next).unwrap_or(b);
+                a.bezier(out_tan, in_tan, b, t)
+            }
+        };
+        Some(value)
+    }
+}
+
+/// Keys without tangents fall back to secants in Hermite tracks and to their
+/// values as control points in Bezier ones
+fn out_tan<T: Clone>(track: &Track<T>) -> Option<&T> {
+    match track {
+        Track::Complex { out_tan, .. } => Some(out_tan),
+        Track::Linear { .. } => None,
+    }
+}
+
+fn in_tan<T: Clone>(track: &Track<T>) -> Option<&T> {
+    match track {
+        Track::Complex { in_tan, .. } => Some(in_tan),
+        Track::Linear { .. } => None,
+    }
+}
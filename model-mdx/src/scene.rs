@@ -0,0 +1,241 @@
+//! Hierarchy of model nodes and their transforms at a moment of the
+//! timeline. Matrices are 4x4 in column-major order.
+use crate::math::{self, Mat4, Quat, Vec3};
+use crate::sampling::Time;
+use crate::types::chunk::Mdlx;
+use crate::types::node::{Node, NodeFlags};
+use std::collections::{BTreeMap, HashMap};
+
+/// Tree of model nodes linked by `parent_id`. Nodes with unknown parents
+/// become roots, as well as the first node of every parent cycle.
+#[derive(Debug, Clone)]
+pub struct Scene<'a> {
+    model: &'a Mdlx,
+    nodes: BTreeMap<u32, &'a Node>,
+    parents: HashMap<u32, u32>,
+    children: HashMap<u32, Vec<u32>>,
+    roots: Vec<u32>,
+    /// Object ids with parents before their children
+    order: Vec<u32>,
+}
+
+impl<'a> Scene<'a> {
+    pub fn new(model: &'a Mdlx) -> Self {
+        let nodes: BTreeMap<u32, &Node> = model.nodes().map(|n| (n.object_id, n)).collect();
+        let mut parents: HashMap<u32, u32> = nodes
+            .values()
+            .filter(|n| n.parent_id != n.object_id && nodes.contains_key(&n.parent_id))
+            .map(|n| (n.object_id, n.parent_id))
+            .collect();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (child, parent) in &parents {
+            children.entry(*parent).or_default().push(*child);
+        }
+        children.values_mut().for_each(|c| c.sort_unstable());
+        let mut roots: Vec<u32> = nodes
+            .keys()
+            .filter(|id| !parents.contains_key(id))
+            .copied()
+            .collect();
+
+        let mut order = vec![];
+        let mut pending = roots.clone();
+        loop {
+            while let Some(id) = pending.pop() {
+                order.push(id);
+                pending.extend(children.get(&id).into_iter().flatten().rev());
+            }
+            // Nodes that are left are trapped in cycles, cut the first one
+            // from its parent
+            if order.len() == nodes.len() {
+                break;
+            }
+            let Some(id) = nodes.keys().find(|id| !order.contains(id)).copied() else {
+                break;
+            };
+            if let Some(parent) = parents.remove(&id) {
+                children.entry(parent).or_default().retain(|c| *c != id);
+            }
+            roots.push(id);
+            pending.push(id);
+        }
+        Scene {
+            model,
+            nodes,
+            parents,
+            children,
+            roots,
+            order,
+        }
+    }
+
+    /// Node with given object id
+    pub fn node(&self, object_id: u32) -> Option<&'a Node> {
+        self.nodes.get(&object_id).copied()
+    }
+
+    /// Object id of parent node, `None` for roots
+    pub fn parent(&self, object_id: u32) -> Option<u32> {
+        self.parents.get(&object_id).copied()
+    }
+
+    /// Object ids of direct children of the node
+    pub fn children(&self, object_id: u32) -> &[u32] {
+        self.children.get(&object_id).map_or(&[], |c| c)
+    }
+
+    /// Object ids of nodes without parents
+    pub fn roots(&self) -> &[u32] {
+        &self.roots
+    }
+
+    /// All nodes, every parent goes before its children
+    pub fn iter(&self) -> impl Iterator<Item = &'a Node> + '_ {
+        self.order.iter().map(|id| self.nodes[id])
+    }
+
+    /// Transforms of all nodes at the moment. Rotation and scaling go
+    /// around pivot points of `PIVT` chunk. Billboarded and camera anchored
+    /// nodes are evaluated as usual nodes.
+    pub fn transforms(&self, time: &Time) -> Transforms {
+        self.evaluate(time, None)
+    }
+
+    /// Transforms of all nodes seen by the camera with the view matrix, the
+    /// matrix must not scale. Billboarded nodes turn their +X axis to the
+    /// camera and ignore own rotation, locked billboards only turn around
+    /// the locked axis. Camera anchored nodes move with the camera, their
+    /// pivot is placed at the camera position.
+    pub fn transforms_with_camera(&self, time: &Time, view: &[f32; 16]) -> Transforms {
+        self.evaluate(time, Some(view))
+    }
+
+    fn evaluate(&self, time: &Time, view: Option<&Mat4>) -> Transforms {
+        let mut result = Transforms::default();
+        // World rotation and scaling are kept apart from matrices to cancel
+        // them for nodes that don't inherit them
+        let mut inherited: HashMap<u32, (Quat, Vec3)> = HashMap::new();
+        for node in self.iter() {
+            let id = node.object_id;
+            let pivot = self.model.pivot(id);
+            let translation = node.kgtr.as_ref().and_then(|t| t.0.sample(time));
+            let rotation = node.kgrt.as_ref().and_then(|t| t.0.sample(time));
+            let scaling = node.kgsc.as_ref().and_then(|t| t.0.sample(time));
+            let translation = translation.unwrap_or_default();
+            let mut rotation = rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            let mut scaling = scaling.unwrap_or([1.0; 3]);
+            result
+                .local
+                .insert(id, around(pivot, translation, rotation, scaling));
+
+            let parent = self.parent(id);
+            let parent_world = parent.map_or(math::IDENTITY, |p| result.world(p));
+            let (parent_rotation, parent_scaling) =
+                parent.map_or(([0.0, 0.0, 0.0, 1.0], [1.0; 3]), |p| inherited[&p]);
+            if node.flags.contains(NodeFlags::DONT_INHERIT_ROTATION) {
+                rotation = math::quat_mul(math::quat_conjugate(parent_rotation), rotation);
+            }
+            if node.flags.contains(NodeFlags::DONT_INHERIT_SCALING) {
+                scaling = std::array::from_fn(|i| {
+                    if parent_scaling[i].abs() > f32::EPSILON {
+                        scaling[i] / parent_scaling[i]
+                    } else {
+                        scaling[i]
+                    }
+                });
+            }
+            if let Some(facing) = view.and_then(|v| billboard(node.flags, v, parent_rotation)) {
+                rotation = facing;
+            }
+            let local = around(pivot, translation, rotation, scaling);
+            let mut world = math::mul(&parent_world, &local);
+            let mut world_rotation = math::quat_mul(parent_rotation, rotation);
+            let mut world_scaling = std::array::from_fn(|i| parent_scaling[i] * scaling[i]);
+            if let Some(parent) =
+                parent.filter(|_| node.flags.contains(NodeFlags::DONT_INHERIT_TRANSLATION))
+            {
+                // Rotation and scaling of the parent don't move its pivot,
+                // so the pivot displacement is the inherited translation
+                let parent_pivot = self.model.pivot(parent);
+                let shift = math::sub(
+                    math::transform_point(&parent_world, parent_pivot),
+                    parent_pivot,
+                );
+                for (i, s) in shift.iter().enumerate() {
+                    world[12 + i] -= s;
+                }
+            }
+            if let Some(view) = view.filter(|_| node.flags.contains(NodeFlags::CAMERA_ANCHORED)) {
+                let camera = math::inverse_rigid(view);
+                let from_pivot =
+                    math::from_trs(math::scale(pivot, -1.0), [0.0, 0.0, 0.0, 1.0], [1.0; 3]);
+                let own = math::mul(&from_pivot, &around(pivot, translation, rotation, scaling));
+                world = math::mul(&camera, &own);
+                let [x, y, z] = [0, 4, 8].map(|i| [camera[i], camera[i + 1], camera[i + 2]]);
+                world_rotation = math::quat_mul(math::quat_from_axes(x, y, z), rotation);
+                world_scaling = scaling;
+            }
+            inherited.insert(id, (world_rotation, world_scaling));
+            result.world.insert(id, world);
+        }
+        result
+    }
+}
+
+/// Rotation relative to the parent that turns billboarded node to the
+/// camera, `None` if the node is not billboarded
+fn billboard(flags: NodeFlags, view: &Mat4, parent_rotation: Quat) -> Option<Quat> {
+    // Rows of the view rotation are camera axes in model space
+    let right = [view[0], view[4], view[8]];
+    let up = [view[1], view[5], view[9]];
+    let back = [view[2], view[6], view[10]];
+    let to_parent = math::quat_conjugate(parent_rotation);
+    if flags.contains(NodeFlags::BILLBOARDED) {
+        // +X looks at the camera, +Y goes right and +Z goes up on screen
+        let facing = math::quat_from_axes(back, right, up);
+        return Some(math::quat_mul(to_parent, facing));
+    }
+    let to_parent = math::from_trs([0.0; 3], to_parent, [1.0; 3]);
+    let [x, y, z] = math::transform_vector(&to_parent, back);
+    if flags.contains(NodeFlags::BILLBOARDED_LOCK_X) {
+        Some(math::quat_from_axis_angle([1.0, 0.0, 0.0], z.atan2(y)))
+    } else if flags.contains(NodeFlags::BILLBOARDED_LOCK_Y) {
+        Some(math::quat_from_axis_angle([0.0, 1.0, 0.0], (-z).atan2(x)))
+    } else if flags.contains(NodeFlags::BILLBOARDED_LOCK_Z) {
+        Some(math::quat_from_axis_angle([0.0, 0.0, 1.0], y.atan2(x)))
+    } else {
+        None
+    }
+}
+
+/// Matrices of nodes at a moment of the timeline
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transforms {
+    local: HashMap<u32, Mat4>,
+    world: HashMap<u32, Mat4>,
+}
+
+impl Transforms {
+    /// Transform of the node by its own tracks, identity for unknown nodes
+    pub fn local(&self, object_id: u32) -> Mat4 {
+        self.local
+            .get(&object_id)
+            .copied()
+            .unwrap_or(math::IDENTITY)
+    }
+
+    /// Transform of the node into model space, identity for unknown nodes
+    pub fn world(&self, object_id: u32) -> Mat4 {
+        self.world
+            .get(&object_id)
+            .copied()
+            .unwrap_or(math::IDENTITY)
+    }
+}
+
+/// Translation, rotation and scaling where the last two go around the pivot
+fn around(pivot: Vec3, translation: Vec3, rotation: Quat, scaling: Vec3) -> Mat4 {
+    let transform = math::from_trs(math::add(pivot, translation), rotation, scaling);
+    let to_pivot = math::from_trs(math::scale(pivot, -1.0), [0.0, 0.0, 0.0, 1.0], [1.0; 3]);
+    math::mul(&transform, &to_pivot)
+}
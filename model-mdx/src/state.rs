@@ -0,0 +1,126 @@
+//! Animated state of geosets and material layers at a moment of the
+//! timeline, ready for renderers and exporters.
+use crate::sampling::Time;
+use crate::types::animation::{GeosetAnimation, GEOSET_ANIMATION_USE_COLOR};
+use crate::types::chunk::Mdlx;
+use crate::types::layer::Layer;
+
+/// Visibility and tint of a geoset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeosetState {
+    /// Opacity from 0 to 1, geoset is hidden at 0
+    pub alpha: f32,
+    /// Tint in RGB order, white if the animation doesn't use color
+    pub color: [f32; 3],
+}
+
+impl Default for GeosetState {
+    fn default() -> Self {
+        GeosetState {
+            alpha: 1.0,
+            color: [1.0; 3],
+        }
+    }
+}
+
+impl GeosetState {
+    pub fn is_visible(&self) -> bool {
+        self.alpha > 0.0
+    }
+}
+
+impl GeosetAnimation {
+    /// Alpha and color of the animated geoset at the moment. Colors are
+    /// stored in BGR order and are returned in RGB.
+    pub fn state(&self, time: &Time) -> GeosetState {
+        let alpha = self.kgao.as_ref().and_then(|t| t.0.sample(time));
+        let mut state = GeosetState {
+            alpha: alpha.unwrap_or(self.alpha).clamp(0.0, 1.0),
+            ..Default::default()
+        };
+        if self.flags & GEOSET_ANIMATION_USE_COLOR != 0 {
+            let color = self.kgac.as_ref().and_then(|t| t.0.sample(time));
+            let [b, g, r] = color.unwrap_or(self.color);
+            state.color = [r, g, b];
+        }
+        state
+    }
+}
+
+/// States of all geosets of the model in order of `GEOS` chunk. Geosets
+/// without animation are opaque and white, the first animation of a
+/// geoset is used if there are several.
+pub fn geoset_states(model: &Mdlx, time: &Time) -> Vec<GeosetState> {
+    let count = model.geos.as_ref().map_or(0, |c| c.geosets.len());
+    let mut states: Vec<Option<GeosetState>> = vec![None; count];
+    for animation in model.geoa.iter().flat_map(|c| &c.animations) {
+        if let Some(state @ None) = states.get_mut(animation.geoset_id as usize) {
+            *state = Some(animation.state(time));
+        }
+    }
+    states.into_iter().map(Option::unwrap_or_default).collect()
+}
+
+/// Effective values of animated layer fields
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerState {
+    /// Texture of the layer, flipbook animation switches it
+    pub texture_id: u32,
+    pub alpha: f32,
+    pub emissive_gain: f32,
+    pub fresnel_color: [f32; 3],
+    pub fresnel_opacity: f32,
+    pub fresnel_team_color: f32,
+}
+
+impl Layer {
+    /// Values of the layer at the moment. Tracks override static values,
+    /// HD fields of layers without `LayerExt` take neutral values: full
+    /// emissive gain, white fresnel color and no fresnel.
+    pub fn state(&self, time: &Time) -> LayerState {
+        let extra = self.extra.as_ref();
+        LayerState {
+            texture_id: self
+                .kmtf
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(self.texture_id),
+            alpha: self
+                .kmta
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(self.alpha),
+            emissive_gain: self
+                .kmte
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(extra.map_or(1.0, |e| e.emissive_gain)),
+            fresnel_color: self
+                .kfc3
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(extra.map_or([1.0; 3], |e| e.fresnel_color)),
+            fresnel_opacity: self
+                .kfca
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(extra.map_or(0.0, |e| e.fresnel_opacity)),
+            fresnel_team_color: self
+                .kftc
+                .as_ref()
+                .and_then(|t| t.0.sample(time))
+                .unwrap_or(extra.map_or(0.0, |e| e.fresnel_team_color)),
+        }
+    }
+}
+
+/// States of layers of all materials of the model, indexed by material and
+/// then by layer
+pub fn layer_states(model: &Mdlx, time: &Time) -> Vec<Vec<LayerState>> {
+    model
+        .mtls
+        .iter()
+        .flat_map(|c| &c.materials)
+        .map(|m| m.layers.iter().map(|l| l.state(time)).collect())
+        .collect()
+}
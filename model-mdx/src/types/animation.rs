@@ -16,7 +16,13 @@ use log::*;
 // KGAO: float alpha
 // KGAC: float[3] color
 
+/// Geoset casts shadow
+pub const GEOSET_ANIMATION_DROP_SHADOW: u32 = 0x1;
+/// Geoset is tinted by `color`, otherwise the color is ignored
+pub const GEOSET_ANIMATION_USE_COLOR: u32 = 0x2;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeosetAnimation {
     pub alpha: f32,
     pub flags: u32,
@@ -107,7 +113,8 @@ impl Materialized for GeosetAnimation {
 
 /// Holds `alpha`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kgao(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kgao(pub TrackChunk<f32>);
 
 impl Chunk for Kgao {
     fn tag() -> Tag {
@@ -130,7 +137,8 @@ impl Materialized for Kgao {
 
 /// Holds `alpha`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kgac(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kgac(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kgac {
     fn tag() -> Tag {
@@ -18,6 +18,7 @@ use log::*;
 pub const ATCH_PATH_LEN: usize = 260;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attachment {
     pub node: Node,
     pub path: Literal<ATCH_PATH_LEN>,
@@ -72,7 +73,8 @@ impl Materialized for Attachment {
 
 /// Holds `visibility`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Katv(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Katv(pub TrackChunk<f32>);
 
 impl Chunk for Katv {
     fn tag() -> Tag {
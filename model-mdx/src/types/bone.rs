@@ -8,6 +8,7 @@ use super::node::Node;
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bone {
     pub node: Node,
     pub geoset_id: u32,
@@ -22,6 +22,7 @@ use log::*;
 pub const CAMERA_NAME_LEN: usize = 80;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera {
     pub name: Literal<CAMERA_NAME_LEN>,
     pub position: [f32; 3],
@@ -136,7 +137,8 @@ impl Materialized for Camera {
 
 /// Holds `translation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kctr(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kctr(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kctr {
     fn tag() -> Tag {
@@ -159,7 +161,8 @@ impl Materialized for Kctr {
 
 /// Holds `rotation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kcrl(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kcrl(pub TrackChunk<f32>);
 
 impl Chunk for Kcrl {
     fn tag() -> Tag {
@@ -182,7 +185,8 @@ impl Materialized for Kcrl {
 
 /// Holds `target_translation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kttr(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kttr(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kttr {
     fn tag() -> Tag {
@@ -1,11 +1,12 @@
 use super::utils::Tag;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::{primitives::*, Parser};
+use crate::parser::{ParseContext, Parser};
 use crate::types::attachment::Attachment;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Atch {
     pub attachments: Vec<Attachment>,
 }
@@ -16,17 +17,30 @@ impl Chunk for Atch {
     }
 }
 
-impl Materialized for Atch {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Atch {
+    /// Parse the chunk, corrupted attachments are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("ATCH header", Self::expect_header)(input)?;
         let (input, attachments) = context(
             "attachments",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Atch { attachments }))
     }
+}
+
+impl Materialized for Atch {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.attachments)(output))(output)
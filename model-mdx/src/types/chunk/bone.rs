@@ -7,8 +7,9 @@ use crate::parser::Parser;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoneChunk {
-    bones: Vec<Bone>,
+    pub bones: Vec<Bone>,
 }
 
 impl Chunk for BoneChunk {
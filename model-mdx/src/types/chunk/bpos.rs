@@ -1,11 +1,23 @@
-use super::Chunk;
+use super::utils::Tag;
+use super::*;
 use crate::encoder::error::Error as EncodeError;
 use crate::parser::Parser;
-use crate::types::materialize::Materialized;
-use super::utils::Tag;
+use crate::types::materialize::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Bpos {}
+/// Bind pose matrices of nodes for version > 800. Each matrix is 3x4
+/// matrix stored as 12 floats, matrices are indexed by node `object_id`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bpos {
+    pub matrices: Vec<[f32; 12]>,
+}
+
+impl Bpos {
+    /// Get bind matrix for node with given `object_id`
+    pub fn bind_matrix(&self, object_id: u32) -> Option<&[f32; 12]> {
+        self.matrices.get(object_id as usize)
+    }
+}
 
 impl Chunk for Bpos {
     fn tag() -> Tag {
@@ -16,11 +28,13 @@ impl Chunk for Bpos {
 impl Materialized for Bpos {
     type Version = u32;
 
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
-        unimplemented!();
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, _) = context("BPOS header", Self::expect_header)(input)?;
+        let (input, matrices) = context("bind_pose", parse_len_vec(Materialized::parse))(input)?;
+        Ok((input, Bpos { matrices }))
     }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
-        unimplemented!();
+        encode_chunk::<Self, _>(|output| encode_len_vec(&self.matrices, output))(output)
     }
-}
\ No newline at end of file
+}
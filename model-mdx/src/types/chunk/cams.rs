@@ -1,12 +1,12 @@
 use super::utils::*;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::parse_all;
-use crate::parser::Parser;
+use crate::parser::{ParseContext, Parser};
 use crate::types::camera::Camera;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cams {
     pub cameras: Vec<Camera>,
 }
@@ -17,17 +17,30 @@ impl Chunk for Cams {
     }
 }
 
-impl Materialized for Cams {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Cams {
+    /// Parse the chunk, corrupted cameras are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("CAMS header", Self::expect_header)(input)?;
         let (input, cameras) = context(
             "cameras",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Cams { cameras }))
     }
+}
+
+impl Materialized for Cams {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.cameras)(output))(output)
@@ -7,6 +7,7 @@ use crate::types::collision::CollisionShape;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Clid {
     pub shapes: Vec<CollisionShape>,
 }
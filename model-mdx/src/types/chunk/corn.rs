@@ -1,11 +1,18 @@
-use super::Chunk;
-use crate::encoder::error::Error as EncodeError;
-use crate::parser::Parser;
-use crate::types::materialize::Materialized;
 use super::utils::Tag;
+use super::*;
+use crate::encoder::error::Error as EncodeError;
+use crate::parser::{ParseContext, Parser};
+use crate::types::{
+    emitter::CornEmitter,
+    materialize::{parse_all_inclusive, Materialized},
+};
+use nom::error::context;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Corn {}
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Corn {
+    pub emitters: Vec<CornEmitter>,
+}
 
 impl Chunk for Corn {
     fn tag() -> Tag {
@@ -13,14 +20,32 @@ impl Chunk for Corn {
     }
 }
 
+impl Corn {
+    /// Parse the chunk, corrupted emitters are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
+        let (input, _) = context("CORN header", Self::expect_header)(input)?;
+        let (input, emitters) = context(
+            "emitters",
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
+        )(input)?;
+        Ok((input, Corn { emitters }))
+    }
+}
+
 impl Materialized for Corn {
     type Version = u32;
 
     fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
-        unimplemented!();
+        Self::parse_with(version, input, &ParseContext::strict())
     }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
-        unimplemented!();
+        encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.emitters)(output))(output)
     }
 }
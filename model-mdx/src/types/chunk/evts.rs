@@ -7,6 +7,7 @@ use crate::types::event::EventObject;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Evts {
     pub events: Vec<EventObject>,
 }
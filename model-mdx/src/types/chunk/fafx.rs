@@ -1,11 +1,15 @@
-use super::Chunk;
+use super::utils::Tag;
+use super::*;
 use crate::encoder::error::Error as EncodeError;
 use crate::parser::Parser;
+use crate::types::face::{FaceEffect, FACE_EFFECT_SIZE};
 use crate::types::materialize::Materialized;
-use super::utils::Tag;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Fafx {}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fafx {
+    pub face_effects: Vec<FaceEffect>,
+}
 
 impl Chunk for Fafx {
     fn tag() -> Tag {
@@ -16,11 +20,12 @@ impl Chunk for Fafx {
 impl Materialized for Fafx {
     type Version = u32;
 
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
-        unimplemented!();
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, face_effects) = parse_fixed_elements_chunk::<FACE_EFFECT_SIZE, Self, _>(input)?;
+        Ok((input, Fafx { face_effects }))
     }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
-        unimplemented!();
+        encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.face_effects)(output))(output)
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,12 @@
 use super::utils::*;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::parse_all;
-use crate::parser::Parser;
+use crate::parser::{ParseContext, Parser};
 use crate::types::animation::GeosetAnimation;
-use crate::types::materialize::Materialized;
+use crate::types::materialize::{parse_all_inclusive, Materialized};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Geoa {
     pub animations: Vec<GeosetAnimation>,
 }
@@ -17,17 +17,30 @@ impl Chunk for Geoa {
     }
 }
 
-impl Materialized for Geoa {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Geoa {
+    /// Parse the chunk, corrupted geoset animations are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("GEOA header", Self::expect_header)(input)?;
         let (input, animations) = context(
             "animations",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Geoa { animations }))
     }
+}
+
+impl Materialized for Geoa {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.animations)(output))(output)
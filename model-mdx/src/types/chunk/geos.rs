@@ -1,10 +1,11 @@
 use super::utils::*;
 use super::*;
-use crate::parser::primitives::parse_all;
+use crate::parser::ParseContext;
 use crate::types::geoset::*;
 use crate::types::materialize::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Geos {
     pub geosets: Vec<Geoset>,
 }
@@ -15,17 +16,30 @@ impl Chunk for Geos {
     }
 }
 
-impl Materialized for Geos {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Geos {
+    /// Parse the chunk, corrupted geosets are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("GEOS header", Self::expect_header)(input)?;
         let (input, geosets) = context(
             "geosets",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Geos { geosets }))
     }
+}
+
+impl Materialized for Geos {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.geosets)(output))(output)
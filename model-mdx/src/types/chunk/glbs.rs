@@ -6,6 +6,7 @@ use crate::types::materialize::*;
 pub const GLOBALS_SIZE: usize = 4;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Glbs {
     pub global_sequences: Vec<u32>, 
 }
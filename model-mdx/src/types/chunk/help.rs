@@ -1,13 +1,14 @@
 use super::utils::Tag;
 use super::{encode_chunk, Chunk};
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::{primitives::parse_all, Parser};
+use crate::parser::{ParseContext, Parser};
 use crate::types::{
-    materialize::{context, encode_fixed_vec, Materialized},
+    materialize::{context, encode_fixed_vec, parse_all_inclusive, Materialized},
     node::Node,
 };
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Help {
     pub helpers: Vec<Node>,
 }
@@ -18,17 +19,30 @@ impl Chunk for Help {
     }
 }
 
-impl Materialized for Help {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Help {
+    /// Parse the chunk, corrupted helpers are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("HELP header", Self::expect_header)(input)?;
         let (input, helpers) = context(
             "helpers",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Help { helpers }))
     }
+}
+
+impl Materialized for Help {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.helpers)(output))(output)
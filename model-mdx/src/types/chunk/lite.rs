@@ -1,13 +1,14 @@
 use super::utils::Tag;
 use super::{encode_chunk, Chunk};
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::{primitives::parse_all, Parser};
+use crate::parser::{ParseContext, Parser};
 use crate::types::{
     light::Light,
-    materialize::{context, encode_fixed_vec, Materialized},
+    materialize::{context, encode_fixed_vec, parse_all_inclusive, Materialized},
 };
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lite {
     pub lights: Vec<Light>,
 }
@@ -18,17 +19,30 @@ impl Chunk for Lite {
     }
 }
 
-impl Materialized for Lite {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Lite {
+    /// Parse the chunk, corrupted lights are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("HELP header", Self::expect_header)(input)?;
         let (input, lights) = context(
             "lights",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Lite { lights }))
     }
+}
+
+impl Materialized for Lite {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.lights)(output))(output)
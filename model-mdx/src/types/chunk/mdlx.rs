@@ -2,8 +2,9 @@ use super::utils::Tag;
 use super::utils::*;
 use super::{parse_subchunks, Chunk};
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::Parser;
+use crate::parser::{ParseContext, Parser};
 use crate::types::materialize::Materialized;
+use crate::types::node::Node;
 use log::*;
 use nom::{bytes::complete::take, error::context};
 
@@ -15,6 +16,7 @@ use super::{
 
 /// Root chunk of the hierarchy
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mdlx {
     pub vers: Option<Vers>,
     pub modl: Option<Modl>,
@@ -41,6 +43,9 @@ pub struct Mdlx {
     pub bpos: Option<Bpos>,
     pub fafx: Option<Fafx>,
     pub corn: Option<Corn>,
+    /// Chunks with tags we don't know how to parse. They are kept as raw bytes
+    /// of their bodies to write them back on encoding.
+    pub unknown_chunks: Vec<(Tag, Vec<u8>)>,
     /// Controls order of chunks when encoding. If the field is empty, the
     /// order is defined by the order of fields here. Otherwise we write down
     /// only chunks with tags enumerated in the following vector. That allows
@@ -76,9 +81,44 @@ impl Mdlx {
             bpos: None,
             fafx: None,
             corn: None,
+            unknown_chunks: vec![],
             ordered: None,
         }
     }
+
+    /// All nodes of the model: bones, lights, helpers, attachments, emitters,
+    /// event objects and collision shapes
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        let bones = self.bone.iter().flat_map(|c| &c.bones).map(|o| &o.node);
+        let lights = self.lite.iter().flat_map(|c| &c.lights).map(|o| &o.node);
+        let helpers = self.help.iter().flat_map(|c| &c.helpers);
+        let attachments = self.atch.iter().flat_map(|c| &c.attachments).map(|o| &o.node);
+        let prem = self.prem.iter().flat_map(|c| &c.emitters).map(|o| &o.node);
+        let pre2 = self.pre2.iter().flat_map(|c| &c.emitters).map(|o| &o.node);
+        let corn = self.corn.iter().flat_map(|c| &c.emitters).map(|o| &o.node);
+        let ribb = self.ribb.iter().flat_map(|c| &c.emitters).map(|o| &o.node);
+        let events = self.evts.iter().flat_map(|c| &c.events).map(|o| &o.node);
+        let shapes = self.clid.iter().flat_map(|c| &c.shapes).map(|o| o.node());
+        bones
+            .chain(lights)
+            .chain(helpers)
+            .chain(attachments)
+            .chain(prem)
+            .chain(pre2)
+            .chain(corn)
+            .chain(ribb)
+            .chain(events)
+            .chain(shapes)
+    }
+
+    /// Pivot point of node with given object id
+    pub fn pivot(&self, object_id: u32) -> [f32; 3] {
+        self.pivt
+            .as_ref()
+            .and_then(|c| c.points.get(object_id as usize))
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
 impl Chunk for Mdlx {
@@ -87,19 +127,23 @@ impl Chunk for Mdlx {
     }
 }
 
-impl Materialized for Mdlx {
-    type Version = u32;
-
-    fn parse_versioned(version_ext: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Mdlx {
+    /// Parse the model, in lenient mode corrupted chunks and objects are
+    /// skipped and reported to the context
+    pub fn parse_with<'a>(
+        version_ext: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("MDLX tag", Self::expect_tag)(input)?;
 
         let mut result = Self::new();
         let mut ordered = vec![];
-        let mut version: Option<Self::Version> = version_ext;
-        let (input, _) = parse_subchunks(|Header { tag, size }, input| {
+        let mut version: Option<u32> = version_ext;
+        let (input, _) = parse_subchunks(parse_context, |Header { tag, size }, input| {
             if tag == Vers::tag() {
                 let (input, chunk) =
-                    context("VERS chunk", |input| Vers::parse_versioned(version, input))(input)?;
+                    context("VERS", |input| Vers::parse_versioned(version, input))(input)?;
                 version = Some(chunk.version);
                 trace!("Version: {}", chunk.version);
                 result.vers = Some(chunk);
@@ -107,145 +151,160 @@ impl Materialized for Mdlx {
                 Ok((input, ()))
             } else if tag == Modl::tag() {
                 let (input, chunk) =
-                    context("MODL chunk", |input| Modl::parse_versioned(version, input))(input)?;
+                    context("MODL", |input| Modl::parse_versioned(version, input))(input)?;
                 result.modl = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Seqs::tag() {
                 let (input, chunk) =
-                    context("SEQS chunk", |input| Seqs::parse_versioned(version, input))(input)?;
+                    context("SEQS", |input| Seqs::parse_versioned(version, input))(input)?;
                 result.seqs = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Glbs::tag() {
                 let (input, chunk) =
-                    context("GLBS chunk", |input| Glbs::parse_versioned(version, input))(input)?;
+                    context("GLBS", |input| Glbs::parse_versioned(version, input))(input)?;
                 result.glbs = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Texs::tag() {
                 let (input, chunk) =
-                    context("TEXS chunk", |input| Texs::parse_versioned(version, input))(input)?;
+                    context("TEXS", |input| Texs::parse_versioned(version, input))(input)?;
                 result.texs = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Snds::tag() {
                 let (input, chunk) =
-                    context("SNDS chunk", |input| Snds::parse_versioned(version, input))(input)?;
+                    context("SNDS", |input| Snds::parse_versioned(version, input))(input)?;
                 result.snds = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Mtls::tag() {
-                let (input, chunk) =
-                    context("MTLS chunk", |input| Mtls::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("MTLS", |input| {
+                    Mtls::parse_with(version, input, parse_context)
+                })(input)?;
                 result.mtls = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Txan::tag() {
-                let (input, chunk) =
-                    context("TXAN chunk", |input| Txan::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("TXAN", |input| {
+                    Txan::parse_with(version, input, parse_context)
+                })(input)?;
                 result.txan = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Geos::tag() {
-                let (input, chunk) =
-                    context("GEOS chunk", |input| Geos::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("GEOS", |input| {
+                    Geos::parse_with(version, input, parse_context)
+                })(input)?;
                 result.geos = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Geoa::tag() {
-                let (input, chunk) =
-                    context("GEOA chunk", |input| Geoa::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("GEOA", |input| {
+                    Geoa::parse_with(version, input, parse_context)
+                })(input)?;
                 result.geoa = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == BoneChunk::tag() {
-                let (input, chunk) = context("BONE chunk", |input| {
-                    BoneChunk::parse_versioned(version, input)
-                })(input)?;
+                let (input, chunk) =
+                    context("BONE", |input| BoneChunk::parse_versioned(version, input))(input)?;
                 result.bone = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Lite::tag() {
-                let (input, chunk) =
-                    context("LITE chunk", |input| Lite::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("LITE", |input| {
+                    Lite::parse_with(version, input, parse_context)
+                })(input)?;
                 result.lite = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Help::tag() {
-                let (input, chunk) =
-                    context("HELP chunk", |input| Help::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("HELP", |input| {
+                    Help::parse_with(version, input, parse_context)
+                })(input)?;
                 result.help = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Atch::tag() {
-                let (input, chunk) =
-                    context("ATCH chunk", |input| Atch::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("ATCH", |input| {
+                    Atch::parse_with(version, input, parse_context)
+                })(input)?;
                 result.atch = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Pivt::tag() {
                 let (input, chunk) =
-                    context("PIVT chunk", |input| Pivt::parse_versioned(version, input))(input)?;
+                    context("PIVT", |input| Pivt::parse_versioned(version, input))(input)?;
                 result.pivt = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Prem::tag() {
-                let (input, chunk) =
-                    context("PREM chunk", |input| Prem::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("PREM", |input| {
+                    Prem::parse_with(version, input, parse_context)
+                })(input)?;
                 result.prem = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Pre2::tag() {
-                let (input, chunk) =
-                    context("PRE2 chunk", |input| Pre2::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("PRE2", |input| {
+                    Pre2::parse_with(version, input, parse_context)
+                })(input)?;
                 result.pre2 = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Ribb::tag() {
-                let (input, chunk) =
-                    context("RIBB chunk", |input| Ribb::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("RIBB", |input| {
+                    Ribb::parse_with(version, input, parse_context)
+                })(input)?;
                 result.ribb = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Evts::tag() {
                 let (input, chunk) =
-                    context("EVTS chunk", |input| Evts::parse_versioned(version, input))(input)?;
+                    context("EVTS", |input| Evts::parse_versioned(version, input))(input)?;
                 result.evts = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Cams::tag() {
-                let (input, chunk) =
-                    context("CAMS chunk", |input| Cams::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("CAMS", |input| {
+                    Cams::parse_with(version, input, parse_context)
+                })(input)?;
                 result.cams = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Clid::tag() {
                 let (input, chunk) =
-                    context("CLID chunk", |input| Clid::parse_versioned(version, input))(input)?;
+                    context("CLID", |input| Clid::parse_versioned(version, input))(input)?;
                 result.clid = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Bpos::tag() {
                 let (input, chunk) =
-                    context("BPOS chunk", |input| Bpos::parse_versioned(version, input))(input)?;
+                    context("BPOS", |input| Bpos::parse_versioned(version, input))(input)?;
                 result.bpos = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Fafx::tag() {
                 let (input, chunk) =
-                    context("FAFX chunk", |input| Fafx::parse_versioned(version, input))(input)?;
+                    context("FAFX", |input| Fafx::parse_versioned(version, input))(input)?;
                 result.fafx = Some(chunk);
+                ordered.push(tag);
                 Ok((input, ()))
             } else if tag == Corn::tag() {
-                let (input, chunk) =
-                    context("CORN chunk", |input| Corn::parse_versioned(version, input))(input)?;
+                let (input, chunk) = context("CORN", |input| {
+                    Corn::parse_with(version, input, parse_context)
+                })(input)?;
                 result.corn = Some(chunk);
                 ordered.push(tag);
                 Ok((input, ()))
             } else {
-                trace!("Unknown chunk {:?}, skipping it", &tag);
-                let (input, _) = context("skip unknown chunk", take(size))(input)?;
+                trace!("Unknown chunk {}, keeping it as raw bytes", &tag);
+                let (input, _) = context("unknown chunk header", Header::parse)(input)?;
+                let (input, body) = context("unknown chunk body", take(size))(input)?;
+                result.unknown_chunks.push((tag, body.to_vec()));
+                ordered.push(tag);
                 Ok((input, ()))
             }
         })(input)?;
@@ -253,186 +312,194 @@ impl Materialized for Mdlx {
         result.ordered = Some(ordered);
         Ok((input, result))
     }
+}
+
+impl Materialized for Mdlx {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.encode_chunks(output, |_| Ok(()))
+    }
+}
+
+impl Mdlx {
+    /// Encode the model calling `flush` each time a top level chunk is written
+    /// to `output`. The handler can move bytes elsewhere and clear the buffer,
+    /// this way only one chunk is kept in memory at a time.
+    pub fn encode_chunks<F>(&self, output: &mut Vec<u8>, mut flush: F) -> Result<(), EncodeError>
+    where
+        F: FnMut(&mut Vec<u8>) -> Result<(), EncodeError>,
+    {
         Self::encode_tag(output)?;
-        if let Some(tags) = &self.ordered {
-            for tag in tags.iter() {
-                if *tag == Vers::tag() {
-                    if let Some(chunk) = &self.vers {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Modl::tag() {
-                    if let Some(chunk) = &self.modl {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Seqs::tag() {
-                    if let Some(chunk) = &self.seqs {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Glbs::tag() {
-                    if let Some(chunk) = &self.glbs {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Texs::tag() {
-                    if let Some(chunk) = &self.texs {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Snds::tag() {
-                    if let Some(chunk) = &self.snds {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Mtls::tag() {
-                    if let Some(chunk) = &self.mtls {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Txan::tag() {
-                    if let Some(chunk) = &self.txan {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Geos::tag() {
-                    if let Some(chunk) = &self.geos {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Geoa::tag() {
-                    if let Some(chunk) = &self.geoa {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == BoneChunk::tag() {
-                    if let Some(chunk) = &self.bone {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Lite::tag() {
-                    if let Some(chunk) = &self.lite {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Help::tag() {
-                    if let Some(chunk) = &self.help {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Atch::tag() {
-                    if let Some(chunk) = &self.atch {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Pivt::tag() {
-                    if let Some(chunk) = &self.pivt {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Prem::tag() {
-                    if let Some(chunk) = &self.prem {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Pre2::tag() {
-                    if let Some(chunk) = &self.pre2 {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Ribb::tag() {
-                    if let Some(chunk) = &self.ribb {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Evts::tag() {
-                    if let Some(chunk) = &self.evts {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Cams::tag() {
-                    if let Some(chunk) = &self.cams {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Clid::tag() {
-                    if let Some(chunk) = &self.clid {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Bpos::tag() {
-                    if let Some(chunk) = &self.bpos {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Fafx::tag() {
-                    if let Some(chunk) = &self.fafx {
-                        chunk.encode(output)?;
-                    }
-                } else if *tag == Corn::tag() {
-                    if let Some(chunk) = &self.corn {
-                        chunk.encode(output)?;
-                    }
+        flush(output)?;
+        let tags = match &self.ordered {
+            Some(tags) => tags.clone(),
+            None => self.default_order(),
+        };
+        let mut unknown_used = vec![false; self.unknown_chunks.len()];
+        for tag in tags.iter() {
+            if *tag == Vers::tag() {
+                if let Some(chunk) = &self.vers {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Modl::tag() {
+                if let Some(chunk) = &self.modl {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Seqs::tag() {
+                if let Some(chunk) = &self.seqs {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Glbs::tag() {
+                if let Some(chunk) = &self.glbs {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Texs::tag() {
+                if let Some(chunk) = &self.texs {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Snds::tag() {
+                if let Some(chunk) = &self.snds {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Mtls::tag() {
+                if let Some(chunk) = &self.mtls {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Txan::tag() {
+                if let Some(chunk) = &self.txan {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Geos::tag() {
+                if let Some(chunk) = &self.geos {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Geoa::tag() {
+                if let Some(chunk) = &self.geoa {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == BoneChunk::tag() {
+                if let Some(chunk) = &self.bone {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Lite::tag() {
+                if let Some(chunk) = &self.lite {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Help::tag() {
+                if let Some(chunk) = &self.help {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Atch::tag() {
+                if let Some(chunk) = &self.atch {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Pivt::tag() {
+                if let Some(chunk) = &self.pivt {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Prem::tag() {
+                if let Some(chunk) = &self.prem {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Pre2::tag() {
+                if let Some(chunk) = &self.pre2 {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Ribb::tag() {
+                if let Some(chunk) = &self.ribb {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Evts::tag() {
+                if let Some(chunk) = &self.evts {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Cams::tag() {
+                if let Some(chunk) = &self.cams {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Clid::tag() {
+                if let Some(chunk) = &self.clid {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Bpos::tag() {
+                if let Some(chunk) = &self.bpos {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Fafx::tag() {
+                if let Some(chunk) = &self.fafx {
+                    chunk.encode(output)?;
+                }
+            } else if *tag == Corn::tag() {
+                if let Some(chunk) = &self.corn {
+                    chunk.encode(output)?;
+                }
+            } else {
+                let found = self
+                    .unknown_chunks
+                    .iter()
+                    .enumerate()
+                    .find(|(i, (t, _))| t == tag && !unknown_used[*i]);
+                if let Some((i, (tag, body))) = found {
+                    unknown_used[i] = true;
+                    encode_unknown_chunk(*tag, body, output)?;
                 } else {
                     warn!("Unknown chunk {:?}, skipping it", &tag);
                 }
             }
-            Ok(())
-        } else {
-            if let Some(chunk) = &self.vers {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.modl {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.seqs {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.glbs {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.texs {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.snds {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.mtls {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.txan {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.geos {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.geoa {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.bone {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.lite {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.help {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.atch {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.pivt {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.prem {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.pre2 {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.ribb {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.evts {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.cams {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.clid {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.bpos {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.fafx {
-                chunk.encode(output)?;
-            }
-            if let Some(chunk) = &self.corn {
-                chunk.encode(output)?;
-            }
-            Ok(())
+            flush(output)?;
         }
+        Ok(())
+    }
+
+    /// Order of chunks for models that are not parsed from bytes
+    fn default_order(&self) -> Vec<Tag> {
+        let known = [
+            (self.vers.is_some(), Vers::tag()),
+            (self.modl.is_some(), Modl::tag()),
+            (self.seqs.is_some(), Seqs::tag()),
+            (self.glbs.is_some(), Glbs::tag()),
+            (self.texs.is_some(), Texs::tag()),
+            (self.snds.is_some(), Snds::tag()),
+            (self.mtls.is_some(), Mtls::tag()),
+            (self.txan.is_some(), Txan::tag()),
+            (self.geos.is_some(), Geos::tag()),
+            (self.geoa.is_some(), Geoa::tag()),
+            (self.bone.is_some(), BoneChunk::tag()),
+            (self.lite.is_some(), Lite::tag()),
+            (self.help.is_some(), Help::tag()),
+            (self.atch.is_some(), Atch::tag()),
+            (self.pivt.is_some(), Pivt::tag()),
+            (self.prem.is_some(), Prem::tag()),
+            (self.pre2.is_some(), Pre2::tag()),
+            (self.ribb.is_some(), Ribb::tag()),
+            (self.evts.is_some(), Evts::tag()),
+            (self.cams.is_some(), Cams::tag()),
+            (self.clid.is_some(), Clid::tag()),
+            (self.bpos.is_some(), Bpos::tag()),
+            (self.fafx.is_some(), Fafx::tag()),
+            (self.corn.is_some(), Corn::tag()),
+        ];
+        known
+            .into_iter()
+            .filter(|(present, _)| *present)
+            .map(|(_, tag)| tag)
+            .chain(self.unknown_chunks.iter().map(|(tag, _)| *tag))
+            .collect()
     }
 }
+
+/// Writes down chunk that we don't know how to parse with its raw body
+fn encode_unknown_chunk(tag: Tag, body: &[u8], output: &mut Vec<u8>) -> Result<(), EncodeError> {
+    let header = Header {
+        tag,
+        size: body.len(),
+    };
+    header.encode(output)?;
+    output.extend(body);
+    Ok(())
+}
@@ -54,7 +54,7 @@ pub use vers::*;
 
 use super::utils::Tag;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::Parser;
+use crate::parser::{ParseContext, ParseWarning, Parser};
 use crate::types::materialize::*;
 use log::*;
 use nom::{combinator::peek, error::context};
@@ -94,33 +94,71 @@ pub trait Chunk: Sized + Materialized {
 }
 
 /// Parse headers of chunks and pass them into user specified handler.
-/// The combinator does this until input is not empty.
-pub fn parse_subchunks<F>(mut body: F) -> impl FnOnce(&[u8]) -> Parser<()>
+/// The combinator does this until input is not empty. In lenient mode
+/// chunks that fail to parse are skipped and reported as warnings.
+pub fn parse_subchunks<'c, F>(
+    parse_context: &'c ParseContext,
+    mut body: F,
+) -> impl FnOnce(&[u8]) -> Parser<()> + 'c
 where
-    F: FnMut(Header, &[u8]) -> Parser<()>,
+    F: FnMut(Header, &[u8]) -> Parser<()> + 'c,
 {
     move |input| {
+        let lenient = parse_context.is_lenient();
         let mut cycle_input: &[u8] = input;
         while !cycle_input.is_empty() {
+            if lenient && cycle_input.len() < Header::size() {
+                parse_context.warn(ParseWarning::TrailingBytes {
+                    offset: parse_context.offset(cycle_input),
+                    size: cycle_input.len(),
+                });
+                return Ok((&cycle_input[cycle_input.len()..], ()));
+            }
             let (input, header): (&[u8], Header) =
                 context("subchunk header", peek(Materialized::parse))(cycle_input)?;
-            let found: String = std::str::from_utf8(&header.tag.0)
-                .map(|s| s.to_owned())
-                .unwrap_or_else(|_| format!("{:?}", header.tag.0));
-            trace!("Found chunk with tag {} and size {}", found, header.size);
-            let inclusive_size = header.size + Header::size();
+            trace!("Found chunk with tag {} and size {}", header.tag, header.size);
+            let tag = header.tag;
+            let inclusive_size = header.size.saturating_add(Header::size());
             if input.len() < inclusive_size {
                 trace!("Rest input: {:?}", input);
+                if lenient {
+                    parse_context.warn(ParseWarning::TruncatedChunk {
+                        tag,
+                        offset: parse_context.offset(input),
+                        size: header.size,
+                        input: input.len(),
+                    });
+                    return Ok((&input[input.len()..], ()));
+                }
                 return Err(nom::Err::Failure(ParseError::ChunkNotEnoughInput {
                     size: header.size,
                     input: input.len(),
                 }));
             }
-            let (leftover, _) = body(header, &input[0..inclusive_size])?;
-            if leftover.len() > 0 {
-                return Err(nom::Err::Failure(ParseError::ChunkLeftover {
-                    input: leftover.len(),
-                }));
+            match body(header, &input[0..inclusive_size]) {
+                Ok((leftover, _)) if !leftover.is_empty() => {
+                    if lenient {
+                        parse_context.warn(ParseWarning::ChunkLeftover {
+                            tag,
+                            offset: parse_context.offset(input),
+                            leftover: leftover.len(),
+                        });
+                    } else {
+                        return Err(nom::Err::Failure(ParseError::ChunkLeftover {
+                            input: leftover.len(),
+                        }));
+                    }
+                }
+                Ok(_) => (),
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) if lenient => {
+                    parse_context.warn(ParseWarning::SkippedChunk {
+                        tag,
+                        offset: parse_context.offset(input),
+                        size: inclusive_size,
+                        error: parse_context.locate(e),
+                    });
+                }
+                Err(e) => return Err(e),
             }
             cycle_input = &input[inclusive_size..];
         }
@@ -145,7 +183,9 @@ where
             trace!("Found tagged data with tag {}", found);
             let (input, need_stop) = body(tag, input)?;
             cycle_input = input;
-            if need_stop { break; }
+            if need_stop {
+                break;
+            }
         }
         Ok((cycle_input, ()))
     }
@@ -170,10 +210,10 @@ where
     F: FnOnce(&mut Vec<u8>) -> Result<(), EncodeError> + Copy,
 {
     move |output| {
-        let mut buff = vec![];
-        body(&mut buff)?;
-        C::encode_header(buff.len(), output)?;
-        output.extend(buff);
-        Ok(())
+        let start = output.len();
+        C::encode_header(0, output)?;
+        body(output)?;
+        let size = output.len() - start - Header::size();
+        patch_size(output, start + Header::size() - 4, size)
     }
 }
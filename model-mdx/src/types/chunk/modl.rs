@@ -10,6 +10,7 @@ pub const MODL_NAME_LENGTH: usize = 80;
 pub const MODL_ANIMATION_FILENAME_LENGTH: usize = 260;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Modl {
     pub name: Literal<MODL_NAME_LENGTH>,
     pub animation_filename: Literal<MODL_ANIMATION_FILENAME_LENGTH>,
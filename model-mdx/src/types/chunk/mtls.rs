@@ -1,33 +1,49 @@
 use super::utils::Tag;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::parse_all;
-use crate::parser::Parser;
-use crate::types::{material::Material, materialize::Materialized};
+use crate::parser::{ParseContext, Parser};
+use crate::types::{
+    material::Material,
+    materialize::{parse_all_inclusive, Materialized},
+};
 use nom::error::context;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mtls {
     pub materials: Vec<Material>,
 }
 
 impl Chunk for Mtls {
     fn tag() -> Tag {
-        Tag([0x4d, 0x54, 0x4c, 0x53]) // MTLS 
+        Tag([0x4d, 0x54, 0x4c, 0x53]) // MTLS
     }
 }
 
-impl Materialized for Mtls {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Mtls {
+    /// Parse the chunk, corrupted materials are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("MTLS header", Self::expect_header)(input)?;
         let (input, materials) = context(
             "materials",
-            parse_all(|input| Material::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Material::parse_with(version, input, parse_context)
+            }),
         )(input)?;
         Ok((input, Mtls { materials }))
     }
+}
+
+impl Materialized for Mtls {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.materials)(output))(output)
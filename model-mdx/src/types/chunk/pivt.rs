@@ -7,6 +7,7 @@ use crate::types::materialize::*;
 pub const PIVT_SIZE: usize = 12;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pivt {
     pub points: Vec<[f32; 3]>,
 }
@@ -1,13 +1,13 @@
 use super::utils::Tag;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::parse_all;
-use crate::parser::Parser;
-use crate::types::{emitter::ParticleEmitter2, materialize::Materialized};
+use crate::parser::{ParseContext, Parser};
+use crate::types::{emitter::ParticleEmitter2, materialize::{parse_all_inclusive, Materialized}};
 use nom::error::context;
 
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pre2 {
     pub emitters: Vec<ParticleEmitter2>,
 }
@@ -18,17 +18,30 @@ impl Chunk for Pre2 {
     }
 }
 
-impl Materialized for Pre2 {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Pre2 {
+    /// Parse the chunk, corrupted emitters are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("PRE2 header", Self::expect_header)(input)?;
         let (input, emitters) = context(
             "emitters",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Pre2 { emitters }))
     }
+}
+
+impl Materialized for Pre2 {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.emitters)(output))(output)
@@ -4,6 +4,7 @@ use crate::types::materialize::*;
 use crate::types::sequence::{Sequence, SEQUENCE_SIZE};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Seqs {
     pub sequences: Vec<Sequence>,
 }
@@ -1,11 +1,15 @@
-use super::Chunk;
+use super::utils::Tag;
+use super::*;
 use crate::encoder::error::Error as EncodeError;
 use crate::parser::Parser;
 use crate::types::materialize::Materialized;
-use super::utils::Tag;
+use crate::types::sound::{SoundTrack, SOUND_TRACK_SIZE};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Snds {}
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snds {
+    pub sound_tracks: Vec<SoundTrack>,
+}
 
 impl Chunk for Snds {
     fn tag() -> Tag {
@@ -17,10 +21,11 @@ impl Materialized for Snds {
     type Version = u32;
 
     fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
-        unimplemented!();
+        let (input, sound_tracks) = parse_fixed_elements_chunk::<SOUND_TRACK_SIZE, Self, _>(input)?;
+        Ok((input, Snds { sound_tracks }))
     }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
-        unimplemented!();
+        encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.sound_tracks)(output))(output)
     }
 }
@@ -6,6 +6,7 @@ use crate::types::materialize::Materialized;
 use crate::types::texture::{Texture, TEXTURE_SIZE};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Texs {
     pub textures: Vec<Texture>,
 }
@@ -1,12 +1,12 @@
 use super::utils::Tag;
 use super::*;
 use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::parse_all;
-use crate::parser::Parser;
+use crate::parser::{ParseContext, Parser};
 use crate::types::{materialize::Materialized, texture::TextureAnimation};
 use nom::error::context;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Txan {
     pub animations: Vec<TextureAnimation>,
 }
@@ -17,17 +17,30 @@ impl Chunk for Txan {
     }
 }
 
-impl Materialized for Txan {
-    type Version = u32;
-
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Txan {
+    /// Parse the chunk, corrupted texture animations are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         let (input, _) = context("TXAN header", Self::expect_header)(input)?;
         let (input, animations) = context(
             "animations",
-            parse_all(|input| Materialized::parse_versioned(version, input)),
+            parse_all_inclusive(parse_context, |input| {
+                Materialized::parse_versioned(version, input)
+            }),
         )(input)?;
         Ok((input, Txan { animations }))
     }
+}
+
+impl Materialized for Txan {
+    type Version = u32;
+
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
         encode_chunk::<Self, _>(|output| encode_fixed_vec(&self.animations)(output))(output)
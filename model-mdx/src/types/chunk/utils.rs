@@ -7,6 +7,7 @@ use std::fmt;
 
 /// Fixed 4-byte tag, usually ASCII  
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tag(pub [u8; 4]);
 
 impl fmt::Display for Tag {
@@ -105,10 +106,18 @@ impl Header {
 
 /// Literal string that has fixed size in bytes
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Literal<const S: usize> {
     pub content: String,
 }
 
+impl<const S: usize> Literal<S> {
+    /// Content of literal up to the first zero byte
+    pub fn text(&self) -> &str {
+        self.content.split('\0').next().unwrap_or_default()
+    }
+}
+
 impl<const S: usize> Materialized for Literal<S> {
     type Version = u32;
 
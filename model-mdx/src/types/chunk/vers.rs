@@ -7,6 +7,7 @@ use crate::types::materialize::Materialized;
 use nom::{error::context, number::complete::le_u32};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vers {
     pub version: u32,
 }
@@ -19,6 +19,7 @@ use log::*;
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CollisionShape {
     Cube {
         node: Node,
@@ -40,6 +41,17 @@ pub enum CollisionShape {
     },
 }
 
+impl CollisionShape {
+    pub fn node(&self) -> &Node {
+        match self {
+            CollisionShape::Cube { node, .. }
+            | CollisionShape::Plane { node, .. }
+            | CollisionShape::Sphere { node, .. }
+            | CollisionShape::Cylinder { node, .. } => node,
+        }
+    }
+}
+
 impl Materialized for CollisionShape {
     type Version = u32;
 
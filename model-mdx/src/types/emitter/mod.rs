@@ -1,7 +1,9 @@
 pub mod model;
+pub mod popcorn;
 pub mod quad;
 pub mod ribbon;
 
 pub use model::*;
+pub use popcorn::*;
 pub use quad::*;
 pub use ribbon::*;
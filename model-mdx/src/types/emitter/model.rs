@@ -30,6 +30,7 @@ use log::*;
 pub const EMITTER_FILENAME_LENGTH: usize = 260;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParticleEmitter {
     pub node: Node,
     pub emission_rate: f32,
@@ -213,7 +214,8 @@ impl Materialized for ParticleEmitter {
 
 /// Holds `emissionRate`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpee(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpee(pub TrackChunk<f32>);
 
 impl Chunk for Kpee {
     fn tag() -> Tag {
@@ -236,7 +238,8 @@ impl Materialized for Kpee {
 
 /// Holds `gravity`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpeg(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpeg(pub TrackChunk<f32>);
 
 impl Chunk for Kpeg {
     fn tag() -> Tag {
@@ -259,7 +262,8 @@ impl Materialized for Kpeg {
 
 /// Holds `longitude`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpln(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpln(pub TrackChunk<f32>);
 
 impl Chunk for Kpln {
     fn tag() -> Tag {
@@ -282,7 +286,8 @@ impl Materialized for Kpln {
 
 /// Holds `latitude`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kplt(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kplt(pub TrackChunk<f32>);
 
 impl Chunk for Kplt {
     fn tag() -> Tag {
@@ -305,7 +310,8 @@ impl Materialized for Kplt {
 
 /// Holds `lifespan`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpel(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpel(pub TrackChunk<f32>);
 
 impl Chunk for Kpel {
     fn tag() -> Tag {
@@ -328,7 +334,8 @@ impl Materialized for Kpel {
 
 /// Holds `speed`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpes(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpes(pub TrackChunk<f32>);
 
 impl Chunk for Kpes {
     fn tag() -> Tag {
@@ -351,7 +358,8 @@ impl Materialized for Kpes {
 
 /// Holds `visibility`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kpev(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpev(pub TrackChunk<f32>);
 
 impl Chunk for Kpev {
     fn tag() -> Tag {
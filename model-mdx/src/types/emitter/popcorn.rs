@@ -0,0 +1,341 @@
+use super::super::{chunk::utils::*, chunk::*, materialize::*, node::Node, tracks::TrackChunk};
+use log::*;
+
+// CornEmitter {
+//     uint32 inclusiveSize
+//     Node node
+//     float lifeSpan
+//     float emissionRate
+//     float speed
+//     float[4] color
+//     uint32 replaceableId
+//     char[260] path
+//     char[260] flags
+//     (KPPA)
+//     (KPPC)
+//     (KPPE)
+//     (KPPL)
+//     (KPPS)
+//     (KPPV)
+//   }
+// KPPA: float alpha
+// KPPC: float[3] color
+// KPPE: float emissionRate
+// KPPL: float lifespan
+// KPPS: float speed
+// KPPV: float visibility
+
+/// Length of `path` field of [CornEmitter]
+pub const CORN_PATH_LEN: usize = 260;
+/// Length of `flags` field of [CornEmitter]
+pub const CORN_FLAGS_LEN: usize = 260;
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CornEmitter {
+    pub node: Node,
+    pub life_span: f32,
+    pub emission_rate: f32,
+    pub speed: f32,
+    pub color: [f32; 4],
+    pub replaceable_id: u32,
+    pub path: Literal<CORN_PATH_LEN>,
+    pub flags: Literal<CORN_FLAGS_LEN>,
+    pub kppa: Option<Kppa>,
+    pub kppc: Option<Kppc>,
+    pub kppe: Option<Kppe>,
+    pub kppl: Option<Kppl>,
+    pub kpps: Option<Kpps>,
+    pub kppv: Option<Kppv>,
+    pub ordered: Option<Vec<Tag>>,
+}
+
+impl Materialized for CornEmitter {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        parse_inclusive_sized(|input| {
+            let (input, node) = context("node", Materialized::parse)(input)?;
+            let (input, life_span) = context("life_span", Materialized::parse)(input)?;
+            let (input, emission_rate) = context("emission_rate", Materialized::parse)(input)?;
+            let (input, speed) = context("speed", Materialized::parse)(input)?;
+            let (input, color) = context("color", Materialized::parse)(input)?;
+            let (input, replaceable_id) = context("replaceable_id", Materialized::parse)(input)?;
+            let (input, path) = context("path", Materialized::parse)(input)?;
+            let (input, flags) = context("flags", Materialized::parse)(input)?;
+            let mut kppa: Option<Kppa> = None;
+            let mut kppc: Option<Kppc> = None;
+            let mut kppe: Option<Kppe> = None;
+            let mut kppl: Option<Kppl> = None;
+            let mut kpps: Option<Kpps> = None;
+            let mut kppv: Option<Kppv> = None;
+            let mut ordered = vec![];
+            let (input, _) = parse_tagged(|tag, input| {
+                if tag == Kppa::tag() {
+                    let (input, chunk) = context("KPPA chunk", Materialized::parse)(input)?;
+                    kppa = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else if tag == Kppc::tag() {
+                    let (input, chunk) = context("KPPC chunk", Materialized::parse)(input)?;
+                    kppc = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else if tag == Kppe::tag() {
+                    let (input, chunk) = context("KPPE chunk", Materialized::parse)(input)?;
+                    kppe = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else if tag == Kppl::tag() {
+                    let (input, chunk) = context("KPPL chunk", Materialized::parse)(input)?;
+                    kppl = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else if tag == Kpps::tag() {
+                    let (input, chunk) = context("KPPS chunk", Materialized::parse)(input)?;
+                    kpps = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else if tag == Kppv::tag() {
+                    let (input, chunk) = context("KPPV chunk", Materialized::parse)(input)?;
+                    kppv = Some(chunk);
+                    ordered.push(tag);
+                    Ok((input, false))
+                } else {
+                    let found: String = format!("{}", tag);
+                    error!("Unknown tag {}", found);
+                    Err(nom::Err::Failure(ParseError::UnknownTag(found)))
+                }
+            })(input)?;
+            Ok((
+                input,
+                CornEmitter {
+                    node,
+                    life_span,
+                    emission_rate,
+                    speed,
+                    color,
+                    replaceable_id,
+                    path,
+                    flags,
+                    kppa,
+                    kppc,
+                    kppe,
+                    kppl,
+                    kpps,
+                    kppv,
+                    ordered: Some(ordered),
+                },
+            ))
+        })(input)
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        encode_inclusive_sized(output, |output| {
+            self.node.encode(output)?;
+            self.life_span.encode(output)?;
+            self.emission_rate.encode(output)?;
+            self.speed.encode(output)?;
+            self.color.encode(output)?;
+            self.replaceable_id.encode(output)?;
+            self.path.encode(output)?;
+            self.flags.encode(output)?;
+            if let Some(ordered) = &self.ordered {
+                for tag in ordered {
+                    if *tag == Kppa::tag() {
+                        if let Some(chunk) = &self.kppa {
+                            chunk.encode(output)?;
+                        }
+                    } else if *tag == Kppc::tag() {
+                        if let Some(chunk) = &self.kppc {
+                            chunk.encode(output)?;
+                        }
+                    } else if *tag == Kppe::tag() {
+                        if let Some(chunk) = &self.kppe {
+                            chunk.encode(output)?;
+                        }
+                    } else if *tag == Kppl::tag() {
+                        if let Some(chunk) = &self.kppl {
+                            chunk.encode(output)?;
+                        }
+                    } else if *tag == Kpps::tag() {
+                        if let Some(chunk) = &self.kpps {
+                            chunk.encode(output)?;
+                        }
+                    } else if *tag == Kppv::tag() {
+                        if let Some(chunk) = &self.kppv {
+                            chunk.encode(output)?;
+                        }
+                    } else {
+                        warn!("Unknown tag {tag}, skipping...");
+                    }
+                }
+            } else {
+                if let Some(chunk) = &self.kppa {
+                    chunk.encode(output)?;
+                }
+                if let Some(chunk) = &self.kppc {
+                    chunk.encode(output)?;
+                }
+                if let Some(chunk) = &self.kppe {
+                    chunk.encode(output)?;
+                }
+                if let Some(chunk) = &self.kppl {
+                    chunk.encode(output)?;
+                }
+                if let Some(chunk) = &self.kpps {
+                    chunk.encode(output)?;
+                }
+                if let Some(chunk) = &self.kppv {
+                    chunk.encode(output)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Holds `alpha`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kppa(pub TrackChunk<f32>);
+
+impl Chunk for Kppa {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x41]) // KPPA
+    }
+}
+
+impl Materialized for Kppa {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPA track", Materialized::parse)(input)?;
+        Ok((input, Kppa(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
+
+/// Holds `color`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kppc(pub TrackChunk<[f32; 3]>);
+
+impl Chunk for Kppc {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x43]) // KPPC
+    }
+}
+
+impl Materialized for Kppc {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPC track", Materialized::parse)(input)?;
+        Ok((input, Kppc(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
+
+/// Holds `emissionRate`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kppe(pub TrackChunk<f32>);
+
+impl Chunk for Kppe {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x45]) // KPPE
+    }
+}
+
+impl Materialized for Kppe {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPE track", Materialized::parse)(input)?;
+        Ok((input, Kppe(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
+
+/// Holds `lifespan`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kppl(pub TrackChunk<f32>);
+
+impl Chunk for Kppl {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x4C]) // KPPL
+    }
+}
+
+impl Materialized for Kppl {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPL track", Materialized::parse)(input)?;
+        Ok((input, Kppl(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
+
+/// Holds `speed`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kpps(pub TrackChunk<f32>);
+
+impl Chunk for Kpps {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x53]) // KPPS
+    }
+}
+
+impl Materialized for Kpps {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPS track", Materialized::parse)(input)?;
+        Ok((input, Kpps(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
+
+/// Holds `visibility`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kppv(pub TrackChunk<f32>);
+
+impl Chunk for Kppv {
+    fn tag() -> Tag {
+        Tag([0x4B, 0x50, 0x50, 0x56]) // KPPV
+    }
+}
+
+impl Materialized for Kppv {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, chunk) = context("KPPV track", Materialized::parse)(input)?;
+        Ok((input, Kppv(chunk)))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.0.encode(output)
+    }
+}
@@ -3,6 +3,7 @@ use log::*;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FilterMode {
     Blend,
     Additive,
@@ -67,6 +68,7 @@ impl Materialized for FilterMode {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeadTail {
     Head,
     Tail,
@@ -176,6 +178,7 @@ impl Materialized for HeadTail {
 // KP2N: float length
 // KP2W: float width
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParticleEmitter2 {
     pub node: Node,
     pub speed: f32,
@@ -446,7 +449,8 @@ impl Materialized for ParticleEmitter2 {
 
 /// Holds `speed`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2s(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2s(pub TrackChunk<f32>);
 
 impl Chunk for Kp2s {
     fn tag() -> Tag {
@@ -469,7 +473,8 @@ impl Materialized for Kp2s {
 
 /// Holds `variation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2r(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2r(pub TrackChunk<f32>);
 
 impl Chunk for Kp2r {
     fn tag() -> Tag {
@@ -492,7 +497,8 @@ impl Materialized for Kp2r {
 
 /// Holds `latitude`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2l(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2l(pub TrackChunk<f32>);
 
 impl Chunk for Kp2l {
     fn tag() -> Tag {
@@ -515,7 +521,8 @@ impl Materialized for Kp2l {
 
 /// Holds `gravity`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2g(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2g(pub TrackChunk<f32>);
 
 impl Chunk for Kp2g {
     fn tag() -> Tag {
@@ -538,7 +545,8 @@ impl Materialized for Kp2g {
 
 /// Holds `emissionRate`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2e(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2e(pub TrackChunk<f32>);
 
 impl Chunk for Kp2e {
     fn tag() -> Tag {
@@ -561,7 +569,8 @@ impl Materialized for Kp2e {
 
 /// Holds `length`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2n(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2n(pub TrackChunk<f32>);
 
 impl Chunk for Kp2n {
     fn tag() -> Tag {
@@ -584,7 +593,8 @@ impl Materialized for Kp2n {
 
 /// Holds `width`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2w(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2w(pub TrackChunk<f32>);
 
 impl Chunk for Kp2w {
     fn tag() -> Tag {
@@ -607,7 +617,8 @@ impl Materialized for Kp2w {
 
 /// Holds `visibility`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kp2v(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kp2v(pub TrackChunk<f32>);
 
 impl Chunk for Kp2v {
     fn tag() -> Tag {
@@ -30,6 +30,7 @@ use log::*;
 // KRTX: uint32 textureSlot
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RibbonEmitter {
     pub node: Node,
     pub height_above: f32,
@@ -212,7 +213,8 @@ impl Materialized for RibbonEmitter {
 
 /// Holds `heightAbove`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Krha(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Krha(pub TrackChunk<f32>);
 
 impl Chunk for Krha {
     fn tag() -> Tag {
@@ -235,7 +237,8 @@ impl Materialized for Krha {
 
 /// Holds `heightBelow`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Krhb(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Krhb(pub TrackChunk<f32>);
 
 impl Chunk for Krhb {
     fn tag() -> Tag {
@@ -258,7 +261,8 @@ impl Materialized for Krhb {
 
 /// Holds `alpha`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kral(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kral(pub TrackChunk<f32>);
 
 impl Chunk for Kral {
     fn tag() -> Tag {
@@ -281,7 +285,8 @@ impl Materialized for Kral {
 
 /// Holds `color`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Krco(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Krco(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Krco {
     fn tag() -> Tag {
@@ -304,7 +309,8 @@ impl Materialized for Krco {
 
 /// Holds `textureSlot`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Krtx(TrackChunk<u32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Krtx(pub TrackChunk<u32>);
 
 impl Chunk for Krtx {
     fn tag() -> Tag {
@@ -327,7 +333,8 @@ impl Materialized for Krtx {
 
 /// Holds `visibility`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Krvs(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Krvs(pub TrackChunk<f32>);
 
 impl Chunk for Krvs {
     fn tag() -> Tag {
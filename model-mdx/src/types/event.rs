@@ -11,6 +11,7 @@ use super::node::Node;
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventObject {
     pub node: Node,
     pub global_sequence_id: u32,
@@ -3,12 +3,46 @@ use crate::parser::primitives::{le_f32, times};
 use nom::error::context;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Extent {
     pub bounds_radius: f32,
     pub minimum: [f32; 3],
     pub maximum: [f32; 3],
 }
 
+impl Extent {
+    /// Bounding box of the points, radius of the bounding sphere is measured
+    /// from the center of the box.
+    pub fn from_points(points: &[[f32; 3]]) -> Self {
+        if points.is_empty() {
+            return Extent {
+                bounds_radius: 0.0,
+                minimum: [0.0; 3],
+                maximum: [0.0; 3],
+            };
+        }
+        let mut minimum = [f32::MAX; 3];
+        let mut maximum = [f32::MIN; 3];
+        for point in points {
+            for i in 0..3 {
+                minimum[i] = minimum[i].min(point[i]);
+                maximum[i] = maximum[i].max(point[i]);
+            }
+        }
+        let center: Vec<f32> = (0..3).map(|i| (minimum[i] + maximum[i]) / 2.0).collect();
+        let bounds_radius = points
+            .iter()
+            .map(|p| (0..3).map(|i| (p[i] - center[i]).powi(2)).sum::<f32>())
+            .fold(0.0, f32::max)
+            .sqrt();
+        Extent {
+            bounds_radius,
+            minimum,
+            maximum,
+        }
+    }
+}
+
 impl Materialized for Extent {
     type Version = u32;
 
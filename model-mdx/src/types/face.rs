@@ -0,0 +1,37 @@
+use super::chunk::utils::*;
+use super::materialize::*;
+
+/// Amount of bytes one face effect record takes. Note that the spec states
+/// 380 bytes, but the fields below take only 340 bytes and that is what
+/// Reforged models actually contain.
+pub const FACE_EFFECT_SIZE: usize = 340;
+/// Length of `target` field of [FaceEffect]
+pub const FACE_EFFECT_TARGET_LEN: usize = 80;
+/// Length of `path` field of [FaceEffect]
+pub const FACE_EFFECT_PATH_LEN: usize = 260;
+
+// FaceEffect {
+//     char[80] target
+//     char[260] path
+//   }
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaceEffect {
+    pub target: Literal<FACE_EFFECT_TARGET_LEN>,
+    pub path: Literal<FACE_EFFECT_PATH_LEN>,
+}
+
+impl Materialized for FaceEffect {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, target) = context("target", Materialized::parse)(input)?;
+        let (input, path) = context("path", Materialized::parse)(input)?;
+        Ok((input, FaceEffect { target, path }))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.target.encode(output)?;
+        self.path.encode(output)
+    }
+}
@@ -2,10 +2,12 @@ use super::chunk::utils::*;
 use super::chunk::*;
 use super::extent::Extent;
 use super::materialize::*;
+use crate::math;
 use log::*;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FaceTypeGroup {
     Points,
     Lines,
@@ -139,6 +141,7 @@ impl Materialized for FaceTypeGroup {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Geoset {
     pub vertex_positions: Vec<[f32; 3]>,
     pub vertex_normals: Vec<[f32; 3]>,
@@ -200,6 +203,156 @@ impl Geoset {
     pub fn uvas_tag() -> Tag {
         Tag([0x55, 0x56, 0x41, 0x53]) // UVAS
     }
+
+    /// Faces converted into list of triangles. Groups of points and lines
+    /// have no area and are skipped.
+    pub fn triangles(&self) -> Vec<[u16; 3]> {
+        let mut triangles = vec![];
+        let mut faces = self.faces.as_slice();
+        for (face_type, count) in self.face_type_groups.iter().zip(&self.face_groups) {
+            let (group, rest) = faces.split_at((*count as usize).min(faces.len()));
+            faces = rest;
+            match face_type {
+                FaceTypeGroup::Triangles => {
+                    triangles.extend(group.chunks_exact(3).map(|t| [t[0], t[1], t[2]]));
+                }
+                FaceTypeGroup::TriangleStrip => {
+                    for (i, t) in group.windows(3).enumerate() {
+                        // Keep winding of odd triangles
+                        if i % 2 == 0 {
+                            triangles.push([t[0], t[1], t[2]]);
+                        } else {
+                            triangles.push([t[1], t[0], t[2]]);
+                        }
+                    }
+                }
+                FaceTypeGroup::TriangleFan | FaceTypeGroup::Polygons => {
+                    if let Some((first, rest)) = group.split_first() {
+                        triangles.extend(rest.windows(2).map(|t| [*first, t[0], t[1]]));
+                    }
+                }
+                FaceTypeGroup::Quads => {
+                    for q in group.chunks_exact(4) {
+                        triangles.push([q[0], q[1], q[2]]);
+                        triangles.push([q[0], q[2], q[3]]);
+                    }
+                }
+                FaceTypeGroup::QuadStrip => {
+                    for q in group.windows(4).step_by(2) {
+                        triangles.push([q[0], q[1], q[3]]);
+                        triangles.push([q[0], q[3], q[2]]);
+                    }
+                }
+                FaceTypeGroup::Points
+                | FaceTypeGroup::Lines
+                | FaceTypeGroup::LineLoop
+                | FaceTypeGroup::LineStrip => (),
+            }
+        }
+        triangles
+    }
+
+    /// Object ids of nodes for each matrix group. Vertices refer the groups
+    /// by index in `vertex_groups`.
+    pub fn matrices(&self) -> Vec<&[u32]> {
+        let mut result = vec![];
+        let mut indices = self.matrix_indicies.as_slice();
+        for count in &self.matrix_groups {
+            let (group, rest) = indices.split_at((*count as usize).min(indices.len()));
+            indices = rest;
+            result.push(group);
+        }
+        result
+    }
+
+    /// Vertex normals averaged from normals of adjacent triangles, weighted
+    /// by area of the triangles.
+    pub fn smooth_normals(&self) -> Vec<[f32; 3]> {
+        let positions = &self.vertex_positions;
+        let mut normals = vec![[0.0; 3]; positions.len()];
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.map(usize::from);
+            if a.max(b).max(c) >= positions.len() {
+                continue;
+            }
+            let normal = math::cross(
+                math::sub(positions[b], positions[a]),
+                math::sub(positions[c], positions[a]),
+            );
+            for i in [a, b, c] {
+                normals[i] = math::add(normals[i], normal);
+            }
+        }
+        normals.into_iter().map(math::normalize).collect()
+    }
+
+    /// Tangents along the U direction of the first texture coordinate set,
+    /// orthogonal to vertex normals. The fourth component is the sign of the
+    /// bitangent, it is negative when texture is mirrored.
+    pub fn smooth_tangents(&self) -> Vec<[f32; 4]> {
+        let positions = &self.vertex_positions;
+        let coords = self
+            .texture_coordinate_sets
+            .first()
+            .map_or(&[][..], |s| &s.texture_coordinates);
+        let mut tangents = vec![[0.0; 3]; positions.len()];
+        let mut bitangents = vec![[0.0; 3]; positions.len()];
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.map(usize::from);
+            if a.max(b).max(c) >= positions.len().min(coords.len()) {
+                continue;
+            }
+            let edge1 = math::sub(positions[b], positions[a]);
+            let edge2 = math::sub(positions[c], positions[a]);
+            let [du1, dv1] = [coords[b][0] - coords[a][0], coords[b][1] - coords[a][1]];
+            let [du2, dv2] = [coords[c][0] - coords[a][0], coords[c][1] - coords[a][1]];
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() <= f32::EPSILON {
+                continue;
+            }
+            let tangent = math::scale(
+                math::sub(math::scale(edge1, dv2), math::scale(edge2, dv1)),
+                1.0 / det,
+            );
+            let bitangent = math::scale(
+                math::sub(math::scale(edge2, du1), math::scale(edge1, du2)),
+                1.0 / det,
+            );
+            for i in [a, b, c] {
+                tangents[i] = math::add(tangents[i], tangent);
+                bitangents[i] = math::add(bitangents[i], bitangent);
+            }
+        }
+        (0..positions.len())
+            .map(|i| {
+                let normal = self
+                    .vertex_normals
+                    .get(i)
+                    .copied()
+                    .unwrap_or([0.0, 0.0, 1.0]);
+                // Gram-Schmidt, any perpendicular direction fits degenerate mapping
+                let mut tangent = math::sub(
+                    tangents[i],
+                    math::scale(normal, math::dot(normal, tangents[i])),
+                );
+                if math::length(tangent) <= f32::EPSILON {
+                    let axis = if normal[0].abs() < 0.9 {
+                        [1.0, 0.0, 0.0]
+                    } else {
+                        [0.0, 1.0, 0.0]
+                    };
+                    tangent = math::cross(axis, normal);
+                }
+                let [x, y, z] = math::normalize(tangent);
+                let sign = if math::dot(math::cross(normal, [x, y, z]), bitangents[i]) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                [x, y, z, sign]
+            })
+            .collect()
+    }
 }
 
 impl Materialized for Geoset {
@@ -348,6 +501,7 @@ impl Materialized for Geoset {
 pub const GEOSET_LOD_NAME_LEN: usize = 80;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LodExtra {
     pub lod: u32,
     pub lod_name: Literal<GEOSET_LOD_NAME_LEN>,
@@ -375,6 +529,7 @@ impl Materialized for LodExtra {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tangents {
     pub tangents: Vec<[f32; 4]>,
 }
@@ -407,10 +562,23 @@ impl Materialized for Tangents {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Skin {
     pub skin: Vec<u8>,
 }
 
+impl Skin {
+    /// Bone indices and weights of the vertex. Bone indices refer the
+    /// `matrix_indicies` of the geoset, weights sum up to 255.
+    pub fn vertex(&self, index: usize) -> Option<([u8; 4], [u8; 4])> {
+        let data = self.skin.get(index * 8..index * 8 + 8)?;
+        Some((
+            [data[0], data[1], data[2], data[3]],
+            [data[4], data[5], data[6], data[7]],
+        ))
+    }
+}
+
 impl Chunk for Skin {
     fn tag() -> Tag {
         Tag([0x53, 0x4B, 0x49, 0x4E]) // SKIN
@@ -439,6 +607,7 @@ impl Materialized for Skin {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextureCoordinateSet {
     pub texture_coordinates: Vec<[f32; 2]>,
 }
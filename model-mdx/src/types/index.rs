@@ -0,0 +1,112 @@
+use super::chunk::utils::{Header, Tag};
+use super::chunk::{parse_subchunks, Chunk, Mdlx, Vers};
+use super::materialize::Materialized;
+use crate::parser::error::{Error as ParseError, MdxParseError};
+use crate::parser::{located, ParseContext, Parser};
+use nom::error::context;
+
+/// Location of top level chunk inside MDX file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkEntry {
+    pub tag: Tag,
+    /// Offset of the chunk header from the start of the file
+    pub offset: usize,
+    /// Size of the chunk body without header
+    pub size: usize,
+}
+
+impl ChunkEntry {
+    /// Offset of the first byte after the chunk
+    pub fn end(&self) -> usize {
+        self.offset + Header::size() + self.size
+    }
+}
+
+/// List of top level chunks of MDX file that allows to decode only
+/// the chunks that are needed, e.g. `MODL`, `SEQS` or `TEXS` for asset browsers.
+#[derive(Debug, Clone)]
+pub struct MdxIndex<'a> {
+    input: &'a [u8],
+    version: Option<u32>,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+impl<'a> MdxIndex<'a> {
+    /// Walk over chunk headers without decoding bodies. Only `VERS` chunk is
+    /// decoded as other chunks depend on the format version.
+    pub fn scan(input: &'a [u8]) -> Result<Self, ParseError> {
+        let (_, (version, chunks)) =
+            context("MDLX", |i| scan_chunks(input, i))(input).map_err(|e| located(input, e))?;
+        Ok(MdxIndex {
+            input,
+            version,
+            chunks,
+        })
+    }
+
+    /// Version of format from `VERS` chunk
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    /// Find first chunk with the given tag
+    pub fn find(&self, tag: Tag) -> Option<&ChunkEntry> {
+        self.chunks.iter().find(|entry| entry.tag == tag)
+    }
+
+    /// Raw bytes of the chunk including its header
+    pub fn raw(&self, entry: &ChunkEntry) -> &'a [u8] {
+        &self.input[entry.offset..entry.end()]
+    }
+
+    /// Decode the first chunk of the given type, returns `None` if the file
+    /// doesn't have such chunk.
+    pub fn decode<C>(&self) -> Option<Result<C, ParseError>>
+    where
+        C: Chunk + Materialized<Version = u32>,
+    {
+        let entry = self.find(C::tag())?;
+        let chunk_input = self.raw(entry);
+        let in_context = |input, name: String| {
+            move |e: MdxParseError<&'a [u8]>| MdxParseError::Context(input, name, Box::new(e))
+        };
+        let result = match C::parse_versioned(self.version, chunk_input) {
+            Ok((leftover, _)) if !leftover.is_empty() => {
+                Err(nom::Err::Failure(MdxParseError::ChunkLeftover {
+                    input: leftover.len(),
+                }))
+            }
+            result => result,
+        };
+        let result = result
+            .map_err(|e| e.map(in_context(chunk_input, entry.tag.to_string())))
+            .map_err(|e| e.map(in_context(self.input, "MDLX".to_owned())));
+        Some(
+            result
+                .map(|(_, chunk)| chunk)
+                .map_err(|e| located(self.input, e)),
+        )
+    }
+}
+
+fn scan_chunks<'a>(
+    origin: &'a [u8],
+    input: &'a [u8],
+) -> Parser<'a, (Option<u32>, Vec<ChunkEntry>)> {
+    let (input, _) = context("MDLX tag", Mdlx::expect_tag)(input)?;
+    let mut version = None;
+    let mut chunks = vec![];
+    let (input, _) = parse_subchunks(&ParseContext::strict(), |Header { tag, size }, input| {
+        if tag == Vers::tag() {
+            let (_, chunk) = context("VERS", |input| Vers::parse_versioned(version, input))(input)?;
+            version = Some(chunk.version);
+        }
+        chunks.push(ChunkEntry {
+            tag,
+            offset: input.as_ptr() as usize - origin.as_ptr() as usize,
+            size,
+        });
+        Ok((&input[input.len()..], ()))
+    })(input)?;
+    Ok((input, (version, chunks)))
+}
@@ -47,6 +47,7 @@ use std::fmt;
 //   }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FilterMode {
     None,
     Transparent,
@@ -117,6 +118,8 @@ impl Materialized for FilterMode {
 }
 
 bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct ShadingFlags: u32 {
         const UNSHADED = 0b00000001;
         const SPHERE_ENV_MAP = 0b00000010;
@@ -143,6 +146,7 @@ impl Materialized for ShadingFlags {
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     pub filter_mode: FilterMode,
     pub shading_flags: ShadingFlags,
@@ -319,6 +323,7 @@ impl Materialized for Layer {
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerExt {
     pub emissive_gain: f32,
     pub fresnel_color: [f32; 3],
@@ -359,7 +364,8 @@ impl Materialized for LayerExt {
 
 /// Holds `texture_id`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kmtf(TrackChunk<u32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kmtf(pub TrackChunk<u32>);
 
 impl Chunk for Kmtf {
     fn tag() -> Tag {
@@ -382,6 +388,7 @@ impl Materialized for Kmtf {
 
 /// Holds `alpha` field
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kmta(pub TrackChunk<f32>);
 
 impl Chunk for Kmta {
@@ -405,6 +412,7 @@ impl Materialized for Kmta {
 
 /// Holds `emissive_gain` field
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kmte(pub TrackChunk<f32>);
 
 impl Chunk for Kmte {
@@ -428,6 +436,7 @@ impl Materialized for Kmte {
 
 /// Holds `fresnel_color`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kfc3(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kfc3 {
@@ -451,6 +460,7 @@ impl Materialized for Kfc3 {
 
 /// Holds `fresnel_alpha`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kfca(pub TrackChunk<f32>);
 
 impl Chunk for Kfca {
@@ -474,11 +484,12 @@ impl Materialized for Kfca {
 
 /// Holds `fresnel_team_color`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Kftc(pub TrackChunk<f32>);
 
 impl Chunk for Kftc {
     fn tag() -> Tag {
-        Tag([0x4B, 0x46, 0x43, 0x41]) // KFTC
+        Tag([0x4B, 0x46, 0x54, 0x43]) // KFTC
     }
 }
 
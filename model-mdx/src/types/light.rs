@@ -7,6 +7,7 @@ use log::*;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LightType {
     Omni,
     Directional,
@@ -93,6 +94,7 @@ impl Materialized for LightType {
 // KLAV: float visibility
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Light {
     pub node: Node,
     pub light_type: LightType,
@@ -280,7 +282,8 @@ impl Materialized for Light {
 
 /// Holds `attenuationStart`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klas(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klas(pub TrackChunk<f32>);
 
 impl Chunk for Klas {
     fn tag() -> Tag {
@@ -303,7 +306,8 @@ impl Materialized for Klas {
 
 /// Holds `attenuationEnd`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klae(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klae(pub TrackChunk<f32>);
 
 impl Chunk for Klae {
     fn tag() -> Tag {
@@ -326,7 +330,8 @@ impl Materialized for Klae {
 
 /// Holds `color`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klac(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klac(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Klac {
     fn tag() -> Tag {
@@ -349,7 +354,8 @@ impl Materialized for Klac {
 
 /// Holds `intensity`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klai(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klai(pub TrackChunk<f32>);
 
 impl Chunk for Klai {
     fn tag() -> Tag {
@@ -372,7 +378,8 @@ impl Materialized for Klai {
 
 /// Holds `ambientIntensity`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klbi(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klbi(pub TrackChunk<f32>);
 
 impl Chunk for Klbi {
     fn tag() -> Tag {
@@ -395,7 +402,8 @@ impl Materialized for Klbi {
 
 /// Holds `ambientIntensity`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klbc(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klbc(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Klbc {
     fn tag() -> Tag {
@@ -418,7 +426,8 @@ impl Materialized for Klbc {
 
 /// Holds `visibility`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Klav(TrackChunk<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Klav(pub TrackChunk<f32>);
 
 impl Chunk for Klav {
     fn tag() -> Tag {
@@ -1,5 +1,7 @@
 use super::materialize::*;
 use super::utils::*;
+use crate::parser::primitives::count;
+use crate::parser::ParseContext;
 use crate::types::layer::Layer;
 use nom::error::context;
 
@@ -19,6 +21,7 @@ pub const MATERIAL_SHADER_LEN: usize = 80;
 pub const LAYS_TAG: Tag = Tag([0x4C, 0x41, 0x59, 0x53]);
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Material {
     pub priority_plane: u32,
     pub flags: u32,
@@ -26,11 +29,13 @@ pub struct Material {
     pub layers: Vec<Layer>,
 }
 
-impl Materialized for Material {
-    type Version = u32;
-
-    /// Parse the chunk from given input
-    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+impl Material {
+    /// Parse the material, corrupted layers are skipped in lenient mode
+    pub fn parse_with<'a>(
+        version: Option<u32>,
+        input: &'a [u8],
+        parse_context: &ParseContext,
+    ) -> Parser<'a, Self> {
         parse_inclusive_sized(|input| {
             let (input, priority_plane) = context("priority_plane", Materialized::parse)(input)?;
             let (input, flags) = context("flags", Materialized::parse)(input)?;
@@ -39,8 +44,18 @@ impl Materialized for Material {
                     input,
                 )?;
             let (input, _) = LAYS_TAG.expect(input)?;
-            let (input, layers) =
-                parse_len_vec(|input| Materialized::parse_versioned(version, input))(input)?;
+            let (input, layers_count): (&[u8], u32) =
+                context("layers_count", Materialized::parse)(input)?;
+            let (input, layers) = context(
+                "layers",
+                count(
+                    parse_inclusive_lenient(parse_context, |input| {
+                        Materialized::parse_versioned(version, input)
+                    }),
+                    layers_count as usize,
+                ),
+            )(input)?;
+            let layers = layers.into_iter().flatten().collect();
             Ok((
                 input,
                 Material {
@@ -52,6 +67,15 @@ impl Materialized for Material {
             ))
         })(input)
     }
+}
+
+impl Materialized for Material {
+    type Version = u32;
+
+    /// Parse the chunk from given input
+    fn parse_versioned(version: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        Self::parse_with(version, input, &ParseContext::strict())
+    }
 
     /// Encode the chunk to byte stream
     fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
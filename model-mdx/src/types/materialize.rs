@@ -1,11 +1,9 @@
 pub use crate::encoder::error::Error as EncodeError;
-use crate::parser::primitives::{le_f32, times};
+use crate::parser::lenient::{ParseContext, ParseWarning};
+use crate::parser::primitives::{count, le_f32, parse_all, times};
 pub use crate::parser::{error::MdxParseError as ParseError, Parser};
 pub use nom::error::context;
-use nom::{
-    multi::count,
-    number::complete::{le_i32, le_u16, le_u32, le_u8},
-};
+use nom::number::complete::{le_i32, le_u16, le_u32, le_u8};
 
 /// Types that can be parsed and encoded to bytes
 pub trait Materialized: Sized {
@@ -68,7 +66,7 @@ where
 /// Parse vector with known length in advance
 pub fn parse_fixed_vec<T: Materialized>(n: usize) -> impl FnOnce(&[u8]) -> Parser<Vec<T>> {
     move |input| {
-        let (input, vec) = context("vector elements", count(Materialized::parse, n))(input)?;
+        let (input, vec) = count(Materialized::parse, n)(input)?;
         Ok((input, vec))
     }
 }
@@ -92,7 +90,7 @@ where
 {
     move |input| {
         let (input, n): (&[u8], u32) = context("vector length", Materialized::parse)(input)?;
-        let (input, vec) = context("vector elements", count(parser, n as usize))(input)?;
+        let (input, vec) = count(parser, n as usize)(input)?;
         Ok((input, vec))
     }
 }
@@ -138,17 +136,59 @@ where
     }
 }
 
+/// Wraps parser of an object that starts with inclusive size. In lenient mode
+/// the object that fails to parse is skipped up to its inclusive size boundary
+/// and reported as warning. Otherwise the error is passed through.
+pub fn parse_inclusive_lenient<'a, 'c, F, T>(
+    parse_context: &'c ParseContext,
+    body: F,
+) -> impl Fn(&'a [u8]) -> Parser<'a, Option<T>> + Copy + 'c
+where
+    F: Fn(&'a [u8]) -> Parser<'a, T> + Copy + 'c,
+{
+    move |input| match body(input) {
+        Ok((input, value)) => Ok((input, Some(value))),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) if parse_context.is_lenient() => {
+            let size = match le_u32::<_, ParseError<&[u8]>>(input) {
+                Ok((_, size)) if size >= 4 && size as usize <= input.len() => size,
+                _ => return Err(nom::Err::Failure(e)),
+            };
+            parse_context.warn(ParseWarning::SkippedObject {
+                offset: parse_context.offset(input),
+                size,
+                error: parse_context.locate(e),
+            });
+            Ok((&input[size as usize..], None))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse objects with inclusive size until all input is consumed. Corrupted
+/// objects are skipped in lenient mode, see [parse_inclusive_lenient].
+pub fn parse_all_inclusive<'a, 'c, F, T>(
+    parse_context: &'c ParseContext,
+    body: F,
+) -> impl Fn(&'a [u8]) -> Parser<'a, Vec<T>> + 'c
+where
+    F: Fn(&'a [u8]) -> Parser<'a, T> + Copy + 'c,
+{
+    move |input| {
+        let (input, values) = parse_all(parse_inclusive_lenient(parse_context, body))(input)?;
+        Ok((input, values.into_iter().flatten().collect()))
+    }
+}
+
 /// Records size of all enclosed encoders and writes before them inlcusive size of `uint32`
 pub fn encode_inclusive_sized<F>(output: &mut Vec<u8>, body: F) -> Result<(), EncodeError>
 where
     F: FnOnce(&mut Vec<u8>) -> Result<(), EncodeError>,
 {
-    let mut buff = vec![];
-    body(&mut buff)?;
-    let inclusive_size = (buff.len() + 4) as u32;
-    inclusive_size.encode(output)?;
-    output.extend(buff);
-    Ok(())
+    let start = output.len();
+    0u32.encode(output)?;
+    body(output)?;
+    let inclusive_size = output.len() - start;
+    patch_size(output, start, inclusive_size)
 }
 
 /// Records size of all enclosed encoders and writes before them exclusive size of `uint32`
@@ -156,11 +196,22 @@ pub fn encode_exclusive_sized<F>(output: &mut Vec<u8>, body: F) -> Result<(), En
 where
     F: FnOnce(&mut Vec<u8>) -> Result<(), EncodeError>,
 {
-    let mut buff = vec![];
-    body(&mut buff)?;
-    let exclusive_size = buff.len() as u32;
-    exclusive_size.encode(output)?;
-    output.extend(buff);
+    let start = output.len();
+    0u32.encode(output)?;
+    body(output)?;
+    let exclusive_size = output.len() - start - 4;
+    patch_size(output, start, exclusive_size)
+}
+
+/// Overwrites placeholder `uint32` at `position` with the actual size. Sizes are
+/// patched in place so nested encoders don't copy their content into parents.
+pub(crate) fn patch_size(
+    output: &mut [u8],
+    position: usize,
+    size: usize,
+) -> Result<(), EncodeError> {
+    let size = u32::try_from(size).map_err(|_| EncodeError::SizeUintOverflow(size))?;
+    output[position..position + 4].copy_from_slice(&size.to_le_bytes());
     Ok(())
 }
 
@@ -364,6 +415,21 @@ impl Materialized for [f32; 4] {
     }
 }
 
+impl Materialized for [f32; 12] {
+    type Version = ();
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        times::<12, f32, _>(le_f32)(input)
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        for v in self {
+            output.extend(v.to_le_bytes());
+        }
+        Ok(())
+    }
+}
+
 impl Materialized for [u8; 2] {
     type Version = ();
 
@@ -7,13 +7,16 @@ pub mod collision;
 pub mod emitter;
 pub mod event;
 pub mod extent;
+pub mod face;
 pub mod geoset;
+pub mod index;
 pub mod layer;
 pub mod light;
 pub mod material;
 pub mod materialize;
 pub mod node;
 pub mod sequence;
+pub mod sound;
 #[cfg(test)]
 mod tests;
 pub mod texture;
@@ -21,9 +24,12 @@ pub mod tracks;
 
 use super::encoder::error::Error as EncodeError;
 use super::parser::error::Error as ParseError;
+use super::parser::{ParseOptions, ParseWarning};
+use std::io::{Read, Write};
 pub use chunk::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MdxModel {
     pub root: Mdlx,
 }
@@ -33,7 +39,81 @@ impl MdxModel {
         super::parser::parse_mdx(slice)
     }
 
+    /// Parse model with given options, returns warnings about skipped
+    /// parts of the model in lenient mode.
+    pub fn from_slice_with(
+        slice: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        super::parser::parse_mdx_with(slice, options)
+    }
+
+    /// Parse model from text MDL format
+    pub fn from_mdl(input: &str) -> Result<Self, crate::mdl::Error> {
+        crate::mdl::parse_mdl(input)
+    }
+
+    /// Write model in text MDL format
+    pub fn to_mdl(&self) -> String {
+        crate::mdl::write_mdl(self)
+    }
+
+    /// Convert Reforged model into classic model of version 800
+    pub fn to_sd(&self) -> Result<Self, crate::convert::Error> {
+        Ok(MdxModel {
+            root: crate::convert::downgrade(&self.root)?,
+        })
+    }
+
+    /// Convert classic model into Reforged model of version 1000
+    pub fn to_hd(
+        &self,
+        options: &crate::convert::UpgradeOptions,
+    ) -> Result<Self, crate::convert::Error> {
+        Ok(MdxModel {
+            root: crate::convert::upgrade(&self.root, options)?,
+        })
+    }
+
+    /// Write geosets in Wavefront OBJ format with MTL material library
+    pub fn to_obj(&self, options: &crate::obj::ObjOptions) -> crate::obj::Obj {
+        crate::obj::write_obj(self, options)
+    }
+
+    /// Append faces of Wavefront OBJ text as a new geoset that is attached
+    /// to the node, returns index of the geoset
+    pub fn append_obj(
+        &mut self,
+        input: &str,
+        object_id: u32,
+        material_id: u32,
+    ) -> Result<usize, crate::obj::Error> {
+        crate::obj::append_obj(&mut self.root, input, object_id, material_id)
+    }
+
+    /// Convert model into glTF 2.0 document with binary buffer
+    #[cfg(feature = "gltf")]
+    pub fn to_gltf(&self) -> crate::gltf::GltfAsset {
+        crate::gltf::export_gltf(self)
+    }
+
+    /// Build model from binary glTF 2.0 (`.glb`)
+    #[cfg(feature = "gltf")]
+    pub fn from_gltf(slice: &[u8]) -> Result<Self, crate::gltf::Error> {
+        crate::gltf::import_gltf(slice)
+    }
+
     pub fn to_vec(&self) -> Result<Vec<u8>, EncodeError> {
         super::encoder::encode_mdx(self)
     }
+
+    /// Decode model from the reader, e.g. from opened file
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, ParseError> {
+        super::parser::read_mdx(reader)
+    }
+
+    /// Encode model into the writer chunk by chunk
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), EncodeError> {
+        super::encoder::write_mdx(self, writer)
+    }
 }
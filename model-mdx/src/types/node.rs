@@ -6,6 +6,8 @@ use bitflags::*;
 use log::*;
 
 bitflags! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct NodeFlags: u32 {
         const HELPER = 0x0;
         const DONT_INHERIT_TRANSLATION = 0x1;
@@ -86,6 +88,7 @@ pub const NODE_NAME_LEN: usize = 80;
 // KGSC: float[3] scaling
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     pub name: Literal<NODE_NAME_LEN>,
     pub object_id: u32,
@@ -190,7 +193,8 @@ impl Materialized for Node {
 
 /// Holds `translation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kgtr(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kgtr(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kgtr {
     fn tag() -> Tag {
@@ -213,7 +217,8 @@ impl Materialized for Kgtr {
 
 /// Holds `rotation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kgrt(TrackChunk<[f32; 4]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kgrt(pub TrackChunk<[f32; 4]>);
 
 impl Chunk for Kgrt {
     fn tag() -> Tag {
@@ -236,7 +241,8 @@ impl Materialized for Kgrt {
 
 /// Holds `scaling`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Kgsc(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kgsc(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Kgsc {
     fn tag() -> Tag {
@@ -7,6 +7,7 @@ pub const SEQUENCE_SIZE: usize = 132;
 pub const SEQUENCE_NAME_LENGTH: usize = 80;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sequence {
     pub name: Literal<SEQUENCE_NAME_LENGTH>,
     pub interval: [u32; 2],
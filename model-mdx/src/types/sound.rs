@@ -0,0 +1,49 @@
+use super::chunk::utils::*;
+use super::materialize::*;
+
+/// Amount of bytes one sound track record takes
+pub const SOUND_TRACK_SIZE: usize = 272;
+/// Length of `file_name` field of [SoundTrack]
+pub const SOUND_TRACK_FILENAME_LEN: usize = 260;
+
+// SoundTrack {
+//     char[260] fileName
+//     float volume
+//     float pitch
+//     uint32 flags
+//   }
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoundTrack {
+    pub file_name: Literal<SOUND_TRACK_FILENAME_LEN>,
+    pub volume: f32,
+    pub pitch: f32,
+    pub flags: u32,
+}
+
+impl Materialized for SoundTrack {
+    type Version = u32;
+
+    fn parse_versioned(_: Option<Self::Version>, input: &[u8]) -> Parser<Self> {
+        let (input, file_name) = context("file_name", Materialized::parse)(input)?;
+        let (input, volume) = context("volume", Materialized::parse)(input)?;
+        let (input, pitch) = context("pitch", Materialized::parse)(input)?;
+        let (input, flags) = context("flags", Materialized::parse)(input)?;
+        Ok((
+            input,
+            SoundTrack {
+                file_name,
+                volume,
+                pitch,
+                flags,
+            },
+        ))
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) -> Result<(), EncodeError> {
+        self.file_name.encode(output)?;
+        self.volume.encode(output)?;
+        self.pitch.encode(output)?;
+        self.flags.encode(output)
+    }
+}
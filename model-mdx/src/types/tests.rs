@@ -1,3 +1,4 @@
+use super::materialize::Materialized;
 use super::*;
 use include_dir::{include_dir, Dir};
 use log::*;
@@ -13,6 +14,34 @@ fn mdx_encode_decode(input: &[u8]) {
     assert_eq!(input, &encoded, "Input is not equal encoded!");
 }
 
+/// Model parsed from [MDL_SAMPLE]
+fn sample_model() -> MdxModel {
+    MdxModel::from_mdl(MDL_SAMPLE).expect("parsed")
+}
+
+/// Components are equal up to float errors
+fn close(a: &[f32], b: &[f32]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+}
+
+/// Components rounded to thousandths to compare them exactly
+fn round(v: [f32; 3]) -> [f32; 3] {
+    v.map(|c| (c * 1000.0).round() / 1000.0)
+}
+
+/// Direction `v` transformed by column-major matrix `m`
+fn vector(m: &[f32; 16], v: [f32; 3]) -> [f32; 3] {
+    round(std::array::from_fn(|i| {
+        m[i] * v[0] + m[4 + i] * v[1] + m[8 + i] * v[2]
+    }))
+}
+
+/// Point `p` transformed by column-major matrix `m`
+fn point(m: &[f32; 16], p: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = vector(m, p);
+    round([x + m[12], y + m[13], z + m[14]])
+}
+
 #[test]
 fn test_encode_decode_ident() {
     for entry in ASSETS_DIR.find("**/*.mdx").unwrap() {
@@ -28,3 +57,1108 @@ fn test_encode_decode_ident() {
         }
     }
 }
+
+#[test]
+fn test_snds_encode_decode() {
+    let mut root = Mdlx::new();
+    root.vers = Some(Vers { version: 800 });
+    root.snds = Some(Snds {
+        sound_tracks: vec![sound::SoundTrack {
+            file_name: Literal {
+                content: "Sound\\Units\\Footman.wav".to_owned(),
+            },
+            volume: 0.5,
+            pitch: 1.0,
+            flags: 0,
+        }],
+    });
+    let encoded = MdxModel { root }.to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+}
+
+#[test]
+fn test_bpos_encode_decode() {
+    let mut root = Mdlx::new();
+    root.vers = Some(Vers { version: 1000 });
+    let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+    let shifted = [
+        1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 10.0, 20.0, 30.0,
+    ];
+    root.bpos = Some(Bpos {
+        matrices: vec![identity, shifted],
+    });
+    let encoded = MdxModel { root }.to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+    let model = MdxModel::from_slice(&encoded).expect("parsed");
+    let bpos = model.root.bpos.expect("bpos chunk");
+    assert_eq!(bpos.bind_matrix(1), Some(&shifted));
+    assert_eq!(bpos.bind_matrix(2), None);
+}
+
+#[test]
+fn test_fafx_keeps_order() {
+    let mut root = Mdlx::new();
+    root.vers = Some(Vers { version: 1000 });
+    root.fafx = Some(Fafx {
+        face_effects: vec![face::FaceEffect {
+            target: Literal {
+                content: "Portrait".to_owned(),
+            },
+            path: Literal {
+                content: "Units\\Human\\Footman\\Footman.fxa".to_owned(),
+            },
+        }],
+    });
+    let encoded = MdxModel { root }.to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+    let model = MdxModel::from_slice(&encoded).expect("parsed");
+    assert_eq!(model.root.ordered, Some(vec![Vers::tag(), Fafx::tag()]));
+}
+
+fn corn_model() -> MdxModel {
+    use self::node::{Node, NodeFlags};
+    use self::tracks::{InterpolationType, Track, TrackChunk};
+
+    let mut root = Mdlx::new();
+    root.vers = Some(Vers { version: 1000 });
+    root.corn = Some(Corn {
+        emitters: vec![emitter::CornEmitter {
+            node: Node {
+                name: Literal {
+                    content: "Popcorn".to_owned(),
+                },
+                object_id: 0,
+                parent_id: u32::MAX,
+                flags: NodeFlags::empty(),
+                kgtr: None,
+                kgrt: None,
+                kgsc: None,
+                ordered: None,
+            },
+            life_span: 1.0,
+            emission_rate: 10.0,
+            speed: 2.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            replaceable_id: 0,
+            path: Literal {
+                content: "Effects\\Fire.pkfx".to_owned(),
+            },
+            flags: Literal {
+                content: String::new(),
+            },
+            kppa: None,
+            kppc: None,
+            kppe: None,
+            kppl: None,
+            kpps: None,
+            kppv: Some(emitter::Kppv(TrackChunk {
+                tag: emitter::Kppv::tag(),
+                interpolation_type: InterpolationType::None,
+                global_sequence_id: u32::MAX,
+                tracks: vec![
+                    Track::Linear {
+                        frame: 0,
+                        value: 1.0,
+                    },
+                    Track::Linear {
+                        frame: 100,
+                        value: 0.0,
+                    },
+                ],
+            })),
+            ordered: None,
+        }],
+    });
+    MdxModel { root }
+}
+
+#[test]
+fn test_corn_encode_decode() {
+    let encoded = corn_model().to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+}
+
+#[test]
+fn test_unknown_chunks_preserved() {
+    let xtra = utils::Tag(*b"XTRA");
+    let mut root = Mdlx::new();
+    root.vers = Some(Vers { version: 800 });
+    root.unknown_chunks = vec![(xtra, vec![1, 2, 3]), (xtra, vec![4, 5])];
+    root.ordered = Some(vec![xtra, Vers::tag(), xtra]);
+    let encoded = MdxModel { root: root.clone() }.to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+    let model = MdxModel::from_slice(&encoded).expect("parsed");
+    assert_eq!(model.root, root);
+}
+
+#[test]
+fn test_malformed_input_no_panic() {
+    let encoded = corn_model().to_vec().expect("encoded");
+    for i in 0..encoded.len() {
+        let _ = MdxModel::from_slice(&encoded[0..i]);
+        let mut corrupted = encoded.clone();
+        corrupted[i] = !corrupted[i];
+        let _ = MdxModel::from_slice(&corrupted);
+    }
+
+    // Huge count of tracks must not be trusted for preallocation
+    let mut input = vec![];
+    input.extend(b"KGRT");
+    input.extend(u32::MAX.to_le_bytes());
+    input.extend(0u32.to_le_bytes());
+    input.extend(u32::MAX.to_le_bytes());
+    assert!(tracks::TrackChunk::<[f32; 4]>::parse(&input).is_err());
+}
+
+#[test]
+fn test_lenient_skips_corrupted_objects() {
+    use crate::parser::{ParseOptions, ParseWarning};
+
+    let mut model = corn_model();
+    let corn = model.root.corn.as_mut().expect("corn");
+    corn.emitters.push(corn.emitters[0].clone());
+    let mut encoded = model.to_vec().expect("encoded");
+    // Corrupt the track tag of the second emitter and add garbage at the end
+    let pos = encoded
+        .windows(4)
+        .rposition(|w| w == b"KPPV")
+        .expect("track tag");
+    encoded[pos..pos + 4].copy_from_slice(b"XXXX");
+    encoded.extend([0, 1, 2]);
+
+    assert!(MdxModel::from_slice(&encoded).is_err());
+    let options = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let (parsed, warnings) = MdxModel::from_slice_with(&encoded, &options).expect("parsed");
+    assert_eq!(parsed.root.corn.expect("corn").emitters.len(), 1);
+    assert_eq!(warnings.len(), 2);
+    assert!(matches!(warnings[0], ParseWarning::SkippedObject { .. }));
+    assert_eq!(
+        warnings[1],
+        ParseWarning::TrailingBytes {
+            offset: encoded.len() - 3,
+            size: 3
+        }
+    );
+
+    // Corrupted geoset and layer are dropped from their chunks
+    let mut model = sample_model();
+    let geosets = &mut model.root.geos.as_mut().expect("geosets").geosets;
+    geosets.push(geosets[0].clone());
+    let layers = &mut model.root.mtls.as_mut().expect("materials").materials[0].layers;
+    layers.push(layers[0].clone());
+    let mut encoded = model.to_vec().expect("encoded");
+    for tag in [b"VRTX", b"KMTA"] {
+        let pos = encoded.windows(4).rposition(|w| w == tag).expect("tag");
+        encoded[pos..pos + 4].copy_from_slice(b"XXXX");
+    }
+
+    assert!(MdxModel::from_slice(&encoded).is_err());
+    let (parsed, warnings) = MdxModel::from_slice_with(&encoded, &options).expect("parsed");
+    let root = &parsed.root;
+    assert_eq!(root.geos.as_ref().expect("geosets").geosets.len(), 1);
+    let materials = &root.mtls.as_ref().expect("materials").materials;
+    assert_eq!(materials[0].layers.len(), 1);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .all(|w| matches!(w, ParseWarning::SkippedObject { .. })));
+}
+
+#[test]
+fn test_parse_error_location() {
+    use crate::parser::error::{Error, MdxParseError};
+    use crate::types::tracks::UnknownInterpolationType;
+
+    let mut model = corn_model();
+    let corn = model.root.corn.as_mut().expect("corn");
+    corn.emitters.push(corn.emitters[0].clone());
+    let mut encoded = model.to_vec().expect("encoded");
+    // Break interpolation type of the track in the second emitter
+    let pos = encoded
+        .windows(4)
+        .rposition(|w| w == b"KPPV")
+        .expect("track tag")
+        + 8;
+    encoded[pos..pos + 4].copy_from_slice(&7u32.to_le_bytes());
+
+    match MdxModel::from_slice(&encoded) {
+        Err(Error::Parsing(e)) => {
+            assert_eq!(e.offset, pos);
+            assert_eq!(
+                e.cause,
+                MdxParseError::UnknownInterpolationType(UnknownInterpolationType(7))
+            );
+            assert_eq!(
+                e.path.to_string(),
+                "MDLX/CORN/emitters[1]/KPPV chunk/KPPV track/interpolation_type"
+            );
+        }
+        other => panic!("Expected parsing error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_write_to_read_from() {
+    let mut model = corn_model();
+    model
+        .root
+        .unknown_chunks
+        .push((utils::Tag(*b"XTRA"), vec![1, 2, 3]));
+    let encoded = model.to_vec().expect("encoded");
+    let mut written = vec![];
+    model.write_to(&mut written).expect("written");
+    assert_eq!(encoded, written);
+
+    let parsed = MdxModel::read_from(std::io::Cursor::new(written)).expect("parsed");
+    assert_eq!(parsed.to_vec().expect("reencoded"), encoded);
+}
+
+#[test]
+fn test_index_decodes_single_chunk() {
+    use self::index::MdxIndex;
+
+    let mut model = corn_model();
+    model.root.bpos = Some(Bpos {
+        matrices: vec![[0.0; 12]],
+    });
+    let encoded = model.to_vec().expect("encoded");
+    let index = MdxIndex::scan(&encoded).expect("scanned");
+    assert_eq!(index.version(), Some(1000));
+    let tags: Vec<_> = index.chunks.iter().map(|entry| entry.tag).collect();
+    assert_eq!(tags, vec![Vers::tag(), Bpos::tag(), Corn::tag()]);
+    assert_eq!(index.chunks[0].offset, 4);
+    assert_eq!(index.chunks[0].size, 4);
+    assert_eq!(index.chunks[2].end(), encoded.len());
+
+    let parsed = MdxModel::from_slice(&encoded).expect("parsed");
+    let corn: Corn = index.decode().expect("corn chunk").expect("decoded");
+    assert_eq!(Some(corn), parsed.root.corn);
+    assert!(index.decode::<Seqs>().is_none());
+}
+
+const MDL_SAMPLE: &str = r#"// Exported by a third-party tool
+Version {
+	FormatVersion 1000,
+}
+Model "Sample" {
+	NumGeosets 1,
+	BlendTime 150,
+	MinimumExtent { -1, -1.5, 0 },
+	MaximumExtent { 1, 1.5, 2 },
+	BoundsRadius 2.5,
+}
+Sequences 1 {
+	Anim "Stand" {
+		Interval { 0, 1000 },
+		NonLooping,
+		Rarity 1.#INF,
+	}
+}
+GlobalSequences 1 {
+	Duration 500,
+}
+Textures 1 {
+	Bitmap {
+		Image "Textures\Sample.blp",
+		WrapWidth,
+	}
+}
+Materials 1 {
+	Material {
+		Shader "Shader_HD_DefaultUnit",
+		Layer {
+			FilterMode Blend,
+			TwoSided,
+			static TextureID 0,
+			Alpha 2 {
+				Linear,
+				GlobalSeqId 0,
+				0: 1,
+				250: 0.5,
+			}
+			static EmissiveGain 0.5
+		}
+	}
+}
+Geoset {
+	Vertices 3 {
+		{ 0, 0, 0 },
+		{ 1, 0, 0 },
+		{ 0, 1, 0 },
+	}
+	Normals 3 {
+		{ 0, 0, 1 },
+		{ 0, 0, 1 },
+		{ 0, 0, 1 },
+	}
+	TVertices 3 {
+		{ 0, 0 },
+		{ 1, 0 },
+		{ 0, 1 },
+	}
+	VertexGroup {
+		0,
+		0,
+		0,
+	}
+	Faces 1 3 {
+		Triangles {
+			{ 0, 1, 2 },
+		},
+	}
+	Groups 1 1 {
+		Matrices { 0 },
+	}
+	MinimumExtent { 0, 0, 0 },
+	MaximumExtent { 1, 1, 0 },
+	BoundsRadius 1,
+	Anim {
+		BoundsRadius 1,
+	}
+	MaterialID 0,
+	LevelOfDetail 0,
+	Name "Sample",
+}
+Bone "Root" {
+	ObjectId 0,
+	GeosetId 0,
+	GeosetAnimId None,
+	Billboarded,
+	DontInherit { Rotation },
+	Rotation 2 {
+		Hermite,
+		0: { 0, 0, 0, 1 },
+			InTan { 0, 0, 0, 1 },
+			OutTan { 0, 0, 0, 1 },
+		1000: { 0, 0, 1, 0 },
+	}
+}
+Helper "Helper"
+{
+	ObjectId 1,
+	Parent 0,
+	Translation 1 {
+		DontInterp,
+		0: { 1.0, 2.0, 3.0 },
+	}
+}
+PivotPoints 2 {
+	{ 0, 0, 0 },
+	{ 1, 1, 1 },
+}
+"#;
+
+#[test]
+fn test_mdl_parse() {
+    use self::tracks::{InterpolationType, Track};
+
+    let model = sample_model();
+    let root = &model.root;
+    assert_eq!(root.vers, Some(Vers { version: 1000 }));
+    assert_eq!(root.modl.as_ref().expect("modl").blend_time, 150);
+    let sequence = &root.seqs.as_ref().expect("seqs").sequences[0];
+    assert_eq!(sequence.interval, [0, 1000]);
+    assert_eq!(sequence.rarity, f32::INFINITY);
+    assert_eq!(root.texs.as_ref().expect("texs").textures[0].flags, 1);
+
+    let layer = &root.mtls.as_ref().expect("mtls").materials[0].layers[0];
+    assert_eq!(layer.filter_mode, layer::FilterMode::Blend);
+    assert_eq!(layer.texture_animation_id, u32::MAX);
+    assert_eq!(layer.extra.as_ref().expect("extra").emissive_gain, 0.5);
+    let alpha = &layer.kmta.as_ref().expect("alpha").0;
+    assert_eq!(alpha.global_sequence_id, 0);
+    assert_eq!(
+        alpha.tracks[1],
+        Track::Linear {
+            frame: 250,
+            value: 0.5
+        }
+    );
+
+    let geoset = &root.geos.as_ref().expect("geos").geosets[0];
+    assert_eq!(geoset.faces, vec![0, 1, 2]);
+    assert_eq!(geoset.face_groups, vec![3]);
+    assert_eq!(
+        geoset.texture_coordinate_sets[0].texture_coordinates.len(),
+        3
+    );
+    assert_eq!(geoset.sequence_extents.len(), 1);
+
+    let bone = &root.bone.as_ref().expect("bone").bones[0];
+    assert_eq!(bone.geoset_animation_id, u32::MAX);
+    assert!(bone
+        .node
+        .flags
+        .contains(node::NodeFlags::BONE | node::NodeFlags::BILLBOARDED));
+    assert!(bone
+        .node
+        .flags
+        .contains(node::NodeFlags::DONT_INHERIT_ROTATION));
+    let rotation = &bone.node.kgrt.as_ref().expect("rotation").0;
+    assert_eq!(rotation.interpolation_type, InterpolationType::Hermite);
+    assert!(matches!(
+        rotation.tracks[1],
+        Track::Complex { frame: 1000, .. }
+    ));
+
+    let helper = &root.help.as_ref().expect("help").helpers[0];
+    assert_eq!(helper.parent_id, 0);
+    assert!(helper.kgtr.is_some());
+    assert_eq!(root.pivt.as_ref().expect("pivt").points.len(), 2);
+
+    let encoded = model.to_vec().expect("encoded");
+    mdx_encode_decode(&encoded);
+}
+
+fn mdl_write_read(model: &MdxModel) {
+    let text = model.to_mdl();
+    let parsed = MdxModel::from_mdl(&text).expect("parsed mdl");
+    assert_eq!(text, parsed.to_mdl(), "MDL text is not stable!");
+}
+
+#[test]
+fn test_mdl_write() {
+    let model = sample_model();
+    let text = model.to_mdl();
+    assert_eq!(MdxModel::from_mdl(&text).expect("parsed again"), model);
+    assert!(text.contains("Rarity 1.#INF,"));
+    assert!(text.contains("Shader \"Shader_HD_DefaultUnit\","));
+
+    mdl_write_read(&corn_model());
+    for entry in ASSETS_DIR.find("**/*.mdx").unwrap() {
+        if let Some(file) = entry.as_file() {
+            info!("Testing {}", file.path().display());
+            mdl_write_read(&MdxModel::from_slice(file.contents()).expect("parsed"));
+        }
+    }
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn test_gltf_export() {
+    let model = sample_model();
+    let asset = model.to_gltf();
+    let glb = asset.to_glb().expect("glb");
+    let gltf = ::gltf::Gltf::from_slice(&glb).expect("valid glb");
+
+    let mesh = gltf.meshes().next().expect("mesh");
+    assert_eq!(mesh.primitives().len(), 1);
+    let skin = gltf.skins().next().expect("skin");
+    let joints: Vec<_> = skin.joints().map(|j| j.name().map(str::to_owned)).collect();
+    assert_eq!(
+        joints,
+        vec![Some("Root".to_owned()), Some("Helper".to_owned())]
+    );
+    let helper = gltf.nodes().nth(1).expect("helper");
+    assert_eq!(helper.transform().decomposed().0, [1.0, 1.0, 1.0]);
+
+    let animation = gltf.animations().next().expect("animation");
+    assert_eq!(animation.name(), Some("Stand"));
+    assert_eq!(animation.channels().count(), 2);
+
+    let text = asset.to_gltf("sample.bin").expect("gltf");
+    assert!(text.contains("\"uri\": \"sample.bin\""));
+
+    // Sequence without keys is exported as a clip of the rest pose
+    let mut model = model;
+    let sequences = &mut model.root.seqs.as_mut().expect("seqs").sequences;
+    let mut death = sequences[0].clone();
+    death.name.content = "Death".to_owned();
+    death.interval = [2000, 3000];
+    sequences.push(death);
+    let glb = model.to_gltf().to_glb().expect("glb");
+    let gltf = ::gltf::Gltf::from_slice(&glb).expect("valid glb");
+    let animation = gltf.animations().nth(1).expect("static animation");
+    assert_eq!(animation.name(), Some("Death"));
+    let blob = gltf.blob.as_deref();
+    let channel = animation.channels().next().expect("channel");
+    let times: Vec<f32> = channel
+        .reader(|_| blob)
+        .read_inputs()
+        .expect("inputs")
+        .collect();
+    assert_eq!(times, vec![0.0, 1.0]);
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn test_gltf_import() {
+    use self::tracks::InterpolationType;
+    use crate::gltf::{import_gltf_with, ImportOptions};
+
+    let model = sample_model();
+    let glb = model.to_gltf().to_glb().expect("glb");
+    let imported = MdxModel::from_gltf(&glb).expect("imported");
+    let root = &imported.root;
+
+    let geoset = &root.geos.as_ref().expect("geos").geosets[0];
+    let original = &model.root.geos.as_ref().expect("geos").geosets[0];
+    for (a, b) in geoset
+        .vertex_positions
+        .iter()
+        .zip(&original.vertex_positions)
+    {
+        assert!(close(a, b), "{a:?} != {b:?}");
+    }
+    assert_eq!(geoset.faces, original.faces);
+    assert_eq!(
+        geoset.texture_coordinate_sets,
+        original.texture_coordinate_sets
+    );
+    assert_eq!(geoset.matrix_indicies, vec![0]);
+    assert_eq!(geoset.skin.as_ref().expect("skin").skin[4], 255);
+
+    let bones = &root.bone.as_ref().expect("bones").bones;
+    assert_eq!(bones.len(), 2);
+    assert_eq!(bones[1].node.name.content, "Helper");
+    assert_eq!(bones[1].node.parent_id, 0);
+    assert!(close(&root.pivot(1), &[1.0, 1.0, 1.0]));
+    let translation = &bones[1].node.kgtr.as_ref().expect("translation").0;
+    assert_eq!(translation.interpolation_type, InterpolationType::None);
+    assert!(close(translation.tracks[0].value(), &[1.0, 2.0, 3.0]));
+    let rotation = &bones[0].node.kgrt.as_ref().expect("rotation").0;
+    assert_eq!(rotation.tracks.len(), 2);
+    assert!(close(rotation.tracks[1].value(), &[0.0, 0.0, 1.0, 0.0]));
+
+    let sequence = &root.seqs.as_ref().expect("seqs").sequences[0];
+    assert_eq!(sequence.name.content, "Stand");
+    assert_eq!(sequence.interval, [0, 1000]);
+    let texture = &root.texs.as_ref().expect("texs").textures[0];
+    assert_eq!(texture.file_name.content, "Textures\\white.blp");
+    mdx_encode_decode(&imported.to_vec().expect("encoded"));
+
+    let options = ImportOptions { version: 800 };
+    let imported = import_gltf_with(&glb, &options, |_| None).expect("imported");
+    let geoset = &imported.root.geos.as_ref().expect("geos").geosets[0];
+    assert!(geoset.skin.is_none());
+    assert_eq!(geoset.matrix_groups, vec![1]);
+    assert_eq!(geoset.vertex_groups, vec![0, 0, 0]);
+}
+
+#[test]
+fn test_obj_write() {
+    use crate::obj::ObjOptions;
+
+    let model = sample_model();
+    let options = ObjOptions {
+        material_library: "sample.mtl".to_owned(),
+        ..Default::default()
+    };
+    let obj = model.to_obj(&options);
+    assert!(obj.obj.starts_with("mtllib sample.mtl\no Geoset0\n"));
+    assert!(obj.obj.contains("\nv 1 0 0\n"));
+    assert!(obj.obj.contains("\nvt 1 1\n"));
+    assert!(obj
+        .obj
+        .contains("\nusemtl Material0\nf 1/1/1 2/2/2 3/3/3\n"));
+    assert!(obj.mtl.contains("newmtl Material0\n"));
+    assert!(obj.mtl.contains("map_Kd Textures\\Sample.blp\n"));
+
+    // Root bone turns half around Z axis by the end of the sequence
+    let posed = model.to_obj(&ObjOptions {
+        sequence: Some(0),
+        frame: 1000,
+        ..options
+    });
+    let vertex: Vec<f32> = posed
+        .obj
+        .lines()
+        .filter(|l| l.starts_with("v "))
+        .nth(1)
+        .expect("vertex")
+        .split_whitespace()
+        .skip(1)
+        .map(|v| v.parse().expect("number"))
+        .collect();
+    assert!((vertex[0] + 1.0).abs() < 1e-5 && vertex[1].abs() < 1e-5);
+}
+
+#[test]
+fn test_obj_write_separate_indices() {
+    use crate::obj::ObjOptions;
+
+    let mut model = sample_model();
+    let geosets = &mut model.root.geos.as_mut().expect("geosets").geosets;
+    let mut bare = geosets[0].clone();
+    bare.texture_coordinate_sets.clear();
+    bare.vertex_normals.clear();
+    geosets.insert(0, bare);
+
+    let obj = model.to_obj(&ObjOptions::default()).obj;
+    let count = |prefix| obj.lines().filter(|l| l.starts_with(prefix)).count();
+    assert_eq!(count("v "), 6);
+    assert_eq!(count("vt "), 3);
+    assert_eq!(count("vn "), 3);
+    assert!(obj.contains("\nf 1 2 3\n"));
+    assert!(obj.contains("\nf 4/1/1 5/2/2 6/3/3\n"));
+}
+
+#[test]
+fn test_obj_append() {
+    use crate::obj::Error;
+
+    let mut model = sample_model();
+    let input =
+        "# Quad\nv 0 0 0\nv 2 0 0\nv 2 2 0\nv 0 2 1\nvt 0 0\nvt 1 1\ng Plane\nf 1/1 2/1 3/2 -1/2\n";
+    let index = model.append_obj(input, 1, 0).expect("appended");
+    let geosets = &model.root.geos.as_ref().expect("geosets").geosets;
+    assert_eq!(index, geosets.len() - 1);
+    let geoset = &geosets[index];
+    assert_eq!(geoset.vertex_positions.len(), 4);
+    assert_eq!(geoset.faces, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(geoset.face_groups, vec![6]);
+    assert_eq!(geoset.matrix_groups, vec![1]);
+    assert_eq!(geoset.matrix_indicies, vec![1]);
+    assert_eq!(geoset.vertex_groups, vec![0; 4]);
+    assert_eq!(
+        geoset.texture_coordinate_sets[0].texture_coordinates[2],
+        [1.0, 0.0]
+    );
+    assert_eq!(geoset.extent.minimum, [0.0, 0.0, 0.0]);
+    assert_eq!(geoset.extent.maximum, [2.0, 2.0, 1.0]);
+    assert_eq!(geoset.vertex_normals.len(), 4);
+    assert!(model.to_vec().is_ok());
+
+    assert_eq!(model.append_obj(input, 42, 0), Err(Error::UnknownNode(42)));
+    assert_eq!(
+        model.append_obj("v 0 0 0\nf 1 2 3\n", 1, 0),
+        Err(Error::InvalidIndex { line: 2, index: 2 })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_roundtrip() {
+    // Rarity of the sample sequence is infinite, RON keeps it
+    let model = sample_model();
+    let encoded = model.to_vec().expect("encoded");
+    let text = ron::to_string(&model).expect("serialized");
+    let model2: MdxModel = ron::from_str(&text).expect("deserialized");
+    assert_eq!(model, model2);
+    assert_eq!(encoded, model2.to_vec().expect("encoded again"));
+}
+
+#[test]
+fn test_downgrade() {
+    use self::geoset::{LodExtra, Skin};
+
+    let mut model = sample_model();
+    let geosets = &mut model.root.geos.as_mut().expect("geosets").geosets;
+    geosets[0].matrix_groups = vec![2];
+    geosets[0].matrix_indicies = vec![0, 1];
+    geosets[0].skin = Some(Skin {
+        skin: vec![
+            0, 0, 0, 0, 255, 0, 0, 0, 0, 1, 0, 0, 128, 127, 0, 0, 1, 0, 0, 0, 255, 0, 0, 0,
+        ],
+    });
+    let mut lod = geosets[0].clone();
+    lod.lod_extra = Some(LodExtra {
+        lod: 1,
+        lod_name: geosets[0].lod_extra.clone().expect("lod").lod_name,
+    });
+    geosets.insert(0, lod);
+    let material = &mut model.root.mtls.as_mut().expect("materials").materials[0];
+    material.layers.push(material.layers[0].clone());
+    model.root.bone.as_mut().expect("bones").bones[0].geoset_id = 1;
+
+    let sd = model.to_sd().expect("converted");
+    assert_eq!(sd.root.vers.as_ref().map(|v| v.version), Some(800));
+    let material = &sd.root.mtls.as_ref().expect("materials").materials[0];
+    assert_eq!(material.shader, None);
+    assert_eq!(material.layers.len(), 1);
+    assert_eq!(material.layers[0].extra, None);
+    let geosets = &sd.root.geos.as_ref().expect("geosets").geosets;
+    assert_eq!(geosets.len(), 1);
+    assert_eq!(geosets[0].lod_extra, None);
+    assert_eq!(geosets[0].skin, None);
+    assert_eq!(geosets[0].vertex_groups, vec![0, 1, 2]);
+    assert_eq!(geosets[0].matrix_groups, vec![1, 2, 1]);
+    assert_eq!(geosets[0].matrix_indicies, vec![0, 0, 1, 1]);
+    assert_eq!(sd.root.bone.as_ref().expect("bones").bones[0].geoset_id, 0);
+
+    let encoded = sd.to_vec().expect("encoded");
+    let decoded = MdxModel::from_slice(&encoded).expect("decoded");
+    assert_eq!(decoded.to_vec().expect("encoded again"), encoded);
+}
+
+#[test]
+fn test_upgrade() {
+    use crate::convert::{UpgradeOptions, HD_SHADER};
+
+    let mut model = sample_model();
+    let geoset = &mut model.root.geos.as_mut().expect("geosets").geosets[0];
+    geoset.vertex_groups = vec![0, 1, 0];
+    geoset.matrix_groups = vec![1, 2];
+    geoset.matrix_indicies = vec![1, 0, 1];
+    let sd = model.to_sd().expect("downgraded");
+
+    let hd = sd.to_hd(&UpgradeOptions::default()).expect("upgraded");
+    assert_eq!(hd.root.vers.as_ref().map(|v| v.version), Some(1000));
+    let material = &hd.root.mtls.as_ref().expect("materials").materials[0];
+    assert_eq!(
+        material.shader.as_ref().map(|s| s.content.as_str()),
+        Some(HD_SHADER)
+    );
+    assert_eq!(material.layers.len(), 6);
+    assert!(material.layers.iter().all(|l| l.extra.is_some()));
+    let textures = &hd.root.texs.as_ref().expect("textures").textures;
+    assert_eq!(textures.len(), 6);
+    assert_eq!(
+        textures[material.layers[4].texture_id as usize].replaceable_id,
+        1
+    );
+    let geoset = &hd.root.geos.as_ref().expect("geosets").geosets[0];
+    assert_eq!(geoset.lod_extra.as_ref().map(|l| l.lod), Some(0));
+    assert_eq!(geoset.matrix_indicies, vec![1, 0]);
+    assert_eq!(geoset.matrix_groups, vec![1, 1]);
+    let skin = geoset.skin.as_ref().expect("skin");
+    assert_eq!(skin.vertex(0), Some(([0, 0, 0, 0], [255, 0, 0, 0])));
+    assert_eq!(skin.vertex(1), Some(([1, 0, 0, 0], [128, 127, 0, 0])));
+    let tangents = &geoset.tangents.as_ref().expect("tangents").tangents;
+    assert_eq!(tangents, &vec![[1.0, 0.0, 0.0, 1.0]; 3]);
+    let bpos = hd.root.bpos.as_ref().expect("bind poses");
+    assert_eq!(bpos.matrices.len(), 2);
+    assert_eq!(
+        bpos.bind_matrix(1),
+        Some(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0])
+    );
+
+    let encoded = hd.to_vec().expect("encoded");
+    let decoded = MdxModel::from_slice(&encoded).expect("decoded");
+    assert_eq!(decoded.to_vec().expect("encoded again"), encoded);
+}
+
+#[test]
+fn test_upgrade_object_id_out_of_range() {
+    use crate::convert::{Error, UpgradeOptions};
+
+    let mut model = sample_model();
+    model.root.help.as_mut().expect("helpers").helpers[0].object_id = u32::MAX;
+    let sd = model.to_sd().expect("downgraded");
+    assert_eq!(
+        sd.to_hd(&UpgradeOptions::default()),
+        Err(Error::ObjectIdOutOfRange {
+            object_id: u32::MAX,
+            count: 2
+        })
+    );
+}
+
+#[test]
+fn test_track_sampling() {
+    use self::tracks::{InterpolationType, Track, TrackChunk};
+    use crate::sampling::Time;
+
+    let track = |interpolation_type, global_sequence_id, tracks| TrackChunk {
+        tag: node::Kgtr::tag(),
+        interpolation_type,
+        global_sequence_id,
+        tracks,
+    };
+    let time = |frame| Time {
+        interval: [100, 200],
+        frame,
+        global_frame: frame - 100,
+        global_sequences: vec![40],
+    };
+    let keys = vec![
+        Track::Linear {
+            frame: 0,
+            value: 7.0,
+        },
+        Track::Linear {
+            frame: 100,
+            value: 1.0,
+        },
+        Track::Linear {
+            frame: 200,
+            value: 3.0,
+        },
+    ];
+    let linear = track(InterpolationType::Linear, u32::MAX, keys.clone());
+    assert_eq!(linear.sample(&time(150)), Some(2.0));
+    let none = track(InterpolationType::None, u32::MAX, keys);
+    assert_eq!(none.sample(&time(199)), Some(1.0));
+    assert_eq!(none.sample(&time(200)), Some(3.0));
+
+    let complex = |value: f32, tan: f32| Track::Complex {
+        frame: if value > 1.0 { 200 } else { 100 },
+        value,
+        in_tan: tan,
+        out_tan: tan,
+    };
+    let keys = vec![complex(1.0, 1.0), complex(3.0, 3.0)];
+    let hermite = track(InterpolationType::Hermite, u32::MAX, keys.clone());
+    assert_eq!(hermite.sample(&time(150)), Some(1.75));
+    let bezier = track(InterpolationType::Bezier, u32::MAX, keys);
+    assert_eq!(bezier.sample(&time(150)), Some(2.0));
+
+    // Hermite keys without tangents get secants, Bezier ones are control
+    // points of themselves
+    let linear_key = Track::Linear {
+        frame: 100,
+        value: 1.0,
+    };
+    let keys = vec![linear_key, complex(3.0, 0.0)];
+    let hermite = track(InterpolationType::Hermite, u32::MAX, keys.clone());
+    assert_eq!(hermite.sample(&time(150)), Some(2.25));
+    let bezier = track(InterpolationType::Bezier, u32::MAX, keys);
+    assert_eq!(bezier.sample(&time(150)), Some(0.875));
+
+    // Global sequence of 40 frames loops independently of the interval
+    let global = track(
+        InterpolationType::Linear,
+        0,
+        vec![
+            Track::Linear {
+                frame: 0,
+                value: 0.0,
+            },
+            Track::Linear {
+                frame: 40,
+                value: 4.0,
+            },
+        ],
+    );
+    assert_eq!(global.sample(&time(150)), Some(1.0));
+
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    let rotation = TrackChunk {
+        tag: node::Kgrt::tag(),
+        interpolation_type: InterpolationType::Linear,
+        global_sequence_id: u32::MAX,
+        tracks: vec![
+            Track::Linear {
+                frame: 100,
+                value: [0.0, 0.0, 0.0, 1.0],
+            },
+            Track::Linear {
+                frame: 200,
+                value: [0.0, 0.0, 1.0, 0.0],
+            },
+        ],
+    };
+    let q = rotation.sample(&time(150)).expect("sampled");
+    assert!((q[2] - half).abs() < 1e-6 && (q[3] - half).abs() < 1e-6);
+}
+
+#[test]
+fn test_scene_transforms() {
+    use self::node::{Kgtr, NodeFlags};
+    use self::tracks::{InterpolationType, Track, TrackChunk};
+    use crate::sampling::Time;
+    use crate::scene::Scene;
+
+    let mut model = sample_model();
+    let time = Time::new(&model.root, 0, 1000);
+    {
+        let scene = Scene::new(&model.root);
+        assert_eq!(scene.roots(), &[0]);
+        assert_eq!(scene.children(0), &[1]);
+        assert_eq!(scene.parent(1), Some(0));
+        // Helper moves by (1, 2, 3) and turns with the root around Z
+        let transforms = scene.transforms(&time);
+        assert_eq!(point(&transforms.local(1), [1.0; 3]), [2.0, 3.0, 4.0]);
+        assert_eq!(point(&transforms.world(1), [1.0; 3]), [-2.0, -3.0, 4.0]);
+        assert_eq!(
+            point(&transforms.world(1), [2.0, 1.0, 1.0]),
+            [-3.0, -3.0, 4.0]
+        );
+    }
+
+    let bone = &mut model.root.bone.as_mut().expect("bones").bones[0].node;
+    bone.kgtr = Some(Kgtr(TrackChunk {
+        tag: Kgtr::tag(),
+        interpolation_type: InterpolationType::None,
+        global_sequence_id: u32::MAX,
+        tracks: vec![Track::Linear {
+            frame: 0,
+            value: [0.0, 0.0, 10.0],
+        }],
+    }));
+    let helper = &mut model.root.help.as_mut().expect("helpers").helpers[0];
+    helper.flags = NodeFlags::DONT_INHERIT_ROTATION | NodeFlags::DONT_INHERIT_TRANSLATION;
+    let transforms = Scene::new(&model.root).transforms(&time);
+    assert_eq!(point(&transforms.world(0), [0.0; 3]), [0.0, 0.0, 10.0]);
+    // Pivot still orbits the root, but the helper keeps its orientation and
+    // ignores the root lift
+    assert_eq!(point(&transforms.world(1), [1.0; 3]), [-2.0, -3.0, 4.0]);
+    assert_eq!(
+        point(&transforms.world(1), [2.0, 1.0, 1.0]),
+        [-1.0, -3.0, 4.0]
+    );
+}
+
+#[test]
+fn test_scene_billboards() {
+    use self::node::NodeFlags;
+    use crate::sampling::Time;
+    use crate::scene::Scene;
+
+    let mut model = sample_model();
+    // Cameras look at the origin from +X and +Y with Z going up
+    let from_x = [
+        0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -10.0, 1.0,
+    ];
+    let from_y = [
+        -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -10.0, 1.0,
+    ];
+    let time = Time::new(&model.root, 0, 1000);
+
+    // Billboarded root ignores its half turn and faces the camera
+    let scene = Scene::new(&model.root);
+    let plain = scene.transforms(&time);
+    assert_eq!(vector(&plain.world(0), [1.0, 0.0, 0.0]), [-1.0, 0.0, 0.0]);
+    let seen = scene.transforms_with_camera(&time, &from_x);
+    assert_eq!(vector(&seen.world(0), [1.0, 0.0, 0.0]), [1.0, 0.0, 0.0]);
+    let seen = scene.transforms_with_camera(&time, &from_y);
+    assert_eq!(vector(&seen.world(0), [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+    assert_eq!(vector(&seen.world(0), [0.0, 0.0, 1.0]), [0.0, 0.0, 1.0]);
+
+    // Locked billboard turns around Z against the turned parent
+    let bone = &mut model.root.bone.as_mut().expect("bones").bones[0].node;
+    bone.flags = NodeFlags::BONE;
+    let helper = &mut model.root.help.as_mut().expect("helpers").helpers[0];
+    helper.flags = NodeFlags::BILLBOARDED_LOCK_Z;
+    let seen = Scene::new(&model.root).transforms_with_camera(&time, &from_y);
+    assert_eq!(vector(&seen.world(1), [1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+
+    // Anchored helper keeps own offset from the camera
+    let helper = &mut model.root.help.as_mut().expect("helpers").helpers[0];
+    helper.flags = NodeFlags::CAMERA_ANCHORED;
+    let seen = Scene::new(&model.root).transforms_with_camera(&time, &from_x);
+    assert_eq!(point(&seen.world(1), [1.0; 3]), [13.0, 1.0, 2.0]);
+}
+
+#[test]
+fn test_geoset_skinning() {
+    use self::geoset::Skin;
+    use crate::pose::skin_geoset;
+
+    let mut model = sample_model();
+    // At the end of the sequence the root turns half around Z and the helper
+    // goes to (1, 2, 3) from the root, so the second vertex moves to
+    // (-1, 0, 0) with the root and to (-2, -2, 3) with the helper
+    let geoset = &mut model.root.geos.as_mut().expect("geosets").geosets[0];
+    geoset.vertex_groups = vec![0, 1, 0];
+    geoset.matrix_groups = vec![1, 2];
+    geoset.matrix_indicies = vec![0, 0, 1];
+    let geoset = geoset.clone();
+    let skinned = skin_geoset(&model.root, &geoset, 0, 1000);
+    assert!(close(&skinned.positions[1], &[-1.5, -1.0, 1.5]));
+    assert!(close(&skinned.positions[2], &[0.0, -1.0, 0.0]));
+    assert!(close(&skinned.normals[0], &[0.0, 0.0, 1.0]));
+
+    let mut geoset = geoset;
+    geoset.matrix_groups = vec![1, 1];
+    geoset.matrix_indicies = vec![0, 1];
+    geoset.skin = Some(Skin {
+        skin: vec![
+            0, 0, 0, 0, 255, 0, 0, 0, 0, 1, 0, 0, 51, 204, 0, 0, 1, 0, 0, 0, 255, 0, 0, 0,
+        ],
+    });
+    let skinned = skin_geoset(&model.root, &geoset, 0, 1000);
+    assert!(close(&skinned.positions[1], &[-1.8, -1.6, 2.4]));
+    assert!(close(&skinned.positions[2], &[-1.0, -3.0, 3.0]));
+}
+
+#[test]
+fn test_geoset_states() {
+    use self::animation::{GeosetAnimation, Kgao, GEOSET_ANIMATION_USE_COLOR};
+    use self::chunk::Geoa;
+    use self::tracks::{InterpolationType, Track, TrackChunk};
+    use crate::sampling::Time;
+    use crate::state::geoset_states;
+
+    let mut model = sample_model();
+    let geosets = &mut model.root.geos.as_mut().expect("geosets").geosets;
+    geosets.push(geosets[0].clone());
+    let key = |frame, value| Track::Linear { frame, value };
+    model.root.geoa = Some(Geoa {
+        animations: vec![GeosetAnimation {
+            alpha: 1.0,
+            flags: GEOSET_ANIMATION_USE_COLOR,
+            color: [0.0, 0.5, 1.0],
+            geoset_id: 1,
+            kgao: Some(Kgao(TrackChunk {
+                tag: Kgao::tag(),
+                interpolation_type: InterpolationType::None,
+                global_sequence_id: u32::MAX,
+                tracks: vec![key(0, 1.0), key(500, 0.0)],
+            })),
+            kgac: None,
+            ordered: None,
+        }],
+    });
+
+    let states = geoset_states(&model.root, &Time::new(&model.root, 0, 250));
+    assert_eq!(states.len(), 2);
+    assert!(states[0].is_visible());
+    assert_eq!(states[0].color, [1.0; 3]);
+    assert!(states[1].is_visible());
+    assert_eq!(states[1].color, [1.0, 0.5, 0.0]);
+    let states = geoset_states(&model.root, &Time::new(&model.root, 0, 750));
+    assert!(states[0].is_visible());
+    assert!(!states[1].is_visible());
+
+    let animation = &mut model.root.geoa.as_mut().expect("animations").animations[0];
+    animation.flags = 0;
+    let states = geoset_states(&model.root, &Time::new(&model.root, 0, 250));
+    assert_eq!(states[1].color, [1.0; 3]);
+}
+
+#[test]
+fn test_layer_states() {
+    use self::layer::{Kftc, Kmtf};
+    use self::tracks::{InterpolationType, Track, TrackChunk};
+    use crate::sampling::Time;
+    use crate::state::layer_states;
+
+    let mut model = sample_model();
+    let layer = &mut model.root.mtls.as_mut().expect("materials").materials[0].layers[0];
+    layer.kmtf = Some(Kmtf(TrackChunk {
+        tag: Kmtf::tag(),
+        interpolation_type: InterpolationType::None,
+        global_sequence_id: u32::MAX,
+        tracks: vec![
+            Track::Linear { frame: 0, value: 0 },
+            Track::Linear {
+                frame: 500,
+                value: 1,
+            },
+        ],
+    }));
+    layer.kftc = Some(Kftc(TrackChunk {
+        tag: Kftc::tag(),
+        interpolation_type: InterpolationType::Linear,
+        global_sequence_id: u32::MAX,
+        tracks: vec![
+            Track::Linear {
+                frame: 0,
+                value: 0.0,
+            },
+            Track::Linear {
+                frame: 1000,
+                value: 1.0,
+            },
+        ],
+    }));
+    // Fresnel team color track survives binary roundtrip
+    let model = MdxModel::from_slice(&model.to_vec().expect("encoded")).expect("parsed");
+
+    let states = layer_states(&model.root, &Time::new(&model.root, 0, 125));
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].len(), 1);
+    let state = states[0][0];
+    assert_eq!(state.texture_id, 0);
+    assert_eq!(state.alpha, 0.75);
+    assert_eq!(state.emissive_gain, 0.5);
+    assert_eq!(state.fresnel_team_color, 0.125);
+
+    let state = layer_states(&model.root, &Time::new(&model.root, 0, 750))[0][0];
+    assert_eq!(state.texture_id, 1);
+    assert_eq!(state.fresnel_team_color, 0.75);
+}
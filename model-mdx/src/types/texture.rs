@@ -15,6 +15,7 @@ pub const TEXTURE_FILENAME_LEN: usize = 260;
 //     uint32 flags
 //   }
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Texture {
     pub replaceable_id: u32,
     pub file_name: Literal<TEXTURE_FILENAME_LEN>,
@@ -55,6 +56,7 @@ impl Materialized for Texture {
 // KTAR: float[4] rotation
 // KTAS: float[3] scaling
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextureAnimation {
     pub ktat: Option<Ktat>,
     pub ktar: Option<Ktar>,
@@ -110,7 +112,8 @@ impl Materialized for TextureAnimation {
 
 /// Holds `translation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Ktat(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ktat(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Ktat {
     fn tag() -> Tag {
@@ -133,7 +136,8 @@ impl Materialized for Ktat {
 
 /// Holds `rotation`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Ktar(TrackChunk<[f32; 4]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ktar(pub TrackChunk<[f32; 4]>);
 
 impl Chunk for Ktar {
     fn tag() -> Tag {
@@ -156,7 +160,8 @@ impl Materialized for Ktar {
 
 /// Holds `scaling`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct Ktas(TrackChunk<[f32; 3]>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ktas(pub TrackChunk<[f32; 3]>);
 
 impl Chunk for Ktas {
     fn tag() -> Tag {
@@ -1,9 +1,10 @@
 use super::chunk::utils::*;
 use super::materialize::*;
-use nom::multi::count;
+use crate::parser::primitives::count;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterpolationType {
     None,
     Linear,
@@ -76,6 +77,7 @@ impl Materialized for InterpolationType {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrackChunk<T: Clone> {
     pub tag: Tag,
     pub interpolation_type: InterpolationType,
@@ -134,6 +136,7 @@ impl<T: Clone + Materialized> Materialized for TrackChunk<T> {
 //   }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Track<T: Clone> {
     /// Interpolation is [InterpolationType::None] or [InterpolationType::Linear]
     Linear { frame: i32, value: T },
@@ -146,6 +149,20 @@ pub enum Track<T: Clone> {
     },
 }
 
+impl<T: Clone> Track<T> {
+    pub fn frame(&self) -> i32 {
+        match self {
+            Track::Linear { frame, .. } | Track::Complex { frame, .. } => *frame,
+        }
+    }
+
+    pub fn value(&self) -> &T {
+        match self {
+            Track::Linear { value, .. } | Track::Complex { value, .. } => value,
+        }
+    }
+}
+
 impl<T: Clone + Materialized> Materialized for Track<T> {
     type Version = InterpolationType;
 